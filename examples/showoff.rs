@@ -45,6 +45,7 @@ fn main() {
                 center: Vec2::ZERO,
                 sides: 6, // hexagons are bestagons.
                 feature: RegularPolygonFeature::Radius(100.0),
+                ..Default::default()
             },
             color_index: 0,
         })
@@ -74,8 +75,12 @@ fn handle_input_system(keys: Res<Input<KeyCode>>, mut my_shape: ResMut<MyShape>)
     }
 
     match my_shape.draw_mode {
-        DrawMode::Fill(ref mut _options) => {}
-        DrawMode::Stroke(ref mut options) => {
+        DrawMode::Fill(_) | DrawMode::VariableStroke(_) => {}
+        DrawMode::Stroke(ref mut options)
+        | DrawMode::Outlined {
+            stroke: ref mut options,
+            ..
+        } => {
             if keys.just_pressed(KeyCode::Q) {
                 options.line_width = (options.line_width - 1.0).clamp(1.0, 10.0);
             }
@@ -86,16 +91,21 @@ fn handle_input_system(keys: Res<Input<KeyCode>>, mut my_shape: ResMut<MyShape>)
     }
 
     if keys.just_pressed(KeyCode::F) {
-        if let DrawMode::Fill(_) = my_shape.draw_mode {
-            my_shape.draw_mode = DrawMode::Stroke(
-                StrokeOptions::default()
-                    .with_line_width(5.0)
-                    .with_line_join(bevy_canvas::LineJoin::Round)
-                    .with_line_cap(LineCap::Round),
-            );
-        } else {
-            my_shape.draw_mode = DrawMode::Fill(FillOptions::default());
-        }
+        let stroke_options = StrokeOptions::default()
+            .with_line_width(5.0)
+            .with_line_join(bevy_canvas::LineJoin::Round)
+            .with_line_cap(LineCap::Round);
+        my_shape.draw_mode = match my_shape.draw_mode {
+            DrawMode::Stroke(_) => DrawMode::Fill(FillOptions::default()),
+            DrawMode::Fill(_) => DrawMode::Outlined {
+                fill: FillOptions::default(),
+                stroke: stroke_options,
+                outline_color: Color::WHITE,
+            },
+            DrawMode::Outlined { .. } | DrawMode::VariableStroke(_) => {
+                DrawMode::Stroke(stroke_options)
+            }
+        };
     }
 
     if keys.just_pressed(KeyCode::C) {
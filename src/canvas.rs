@@ -1,11 +1,18 @@
-use bevy::{log::error, render::color::Color};
+use std::{borrow::Cow, collections::BTreeMap, time::Duration};
+
+use bevy::{log::error, math::Vec2, render::color::Color, utils::Instant};
 use tess::{
-    path::Path, BuffersBuilder, FillOptions, FillTessellator, StrokeOptions, StrokeTessellator,
+    path::{IdEvent, Path},
+    BuffersBuilder, FillOptions, FillTessellator, StrokeOptions, StrokeTessellator,
 };
 
-use crate::render::types::{BufferPair, VertexConstructor};
+use crate::{
+    feather::add_fringe,
+    render::types::{BufferPair, CanvasGeometry, ClipRect, RenderState, VertexConstructor},
+    stroke::variable_stroke_outline,
+    CanvasText, CoordinateSpace, Paint, Transform2d, VariableStrokeOptions,
+};
 
-// TODO: Consider adding a "passive" field to get a bit of retained mode.
 /// A Bevy `Resource` that exposes an immediate mode 2D rendering API.
 ///
 /// ## Usage
@@ -24,64 +31,696 @@ use crate::render::types::{BufferPair, VertexConstructor};
 ///         origin: Vec2::ZERO,
 ///         extents: Vec2::splat(100.0),
 ///         anchor_point: RectangleAnchor::TopLeft,
+///         ..Default::default()
 ///     };
 ///
 ///     // ...then draw it!
 ///     canvas.draw(&square, DrawMode::fill_simple(), Color::RED);
 /// }
 /// ```
+///
+/// ## Passive layer
+///
+/// Shapes drawn with [`draw`](Canvas::draw) only last one frame. Shapes that
+/// rarely change can instead be drawn once in the passive layer with
+/// [`draw_passive`](Canvas::draw_passive): their tessellated geometry is kept
+/// across frames until it is replaced or removed through the returned
+/// [`PassiveHandle`].
+///
+/// ## Transforms
+///
+/// Every shape is moved by the current transform of the canvas before being
+/// tessellated. Like in the HTML canvas API, the current transform can be
+/// modified with methods like [`translate`](Canvas::translate) or
+/// [`rotate`](Canvas::rotate), and it can be saved and restored with
+/// [`save`](Canvas::save) and [`restore`](Canvas::restore):
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{common_shapes::Rectangle, Canvas, DrawMode};
+///
+/// fn gizmo_system(mut canvas: ResMut<Canvas>, time: Res<Time>) {
+///     let bar = Rectangle {
+///         extents: Vec2::new(100.0, 10.0),
+///         ..Default::default()
+///     };
+///
+///     canvas
+///         .save()
+///         .translate(Vec2::new(200.0, 0.0))
+///         .rotate(time.seconds_since_startup() as f32)
+///         .draw(&bar, DrawMode::fill_simple(), Color::ORANGE)
+///         .rotate(std::f32::consts::FRAC_PI_2)
+///         .draw(&bar, DrawMode::fill_simple(), Color::ORANGE)
+///         .restore();
+/// }
+/// ```
+///
+/// Since the transform is applied to the path and not to the resulting mesh,
+/// it does not affect stroke widths.
+///
+/// ## Layering
+///
+/// Each shape is drawn at the z-coordinate set with
+/// [`set_z`](Canvas::set_z), which defaults to `0.0`. Shapes with a greater z
+/// are drawn on top of the others, and are sorted against Bevy sprites in the
+/// same way sprites are sorted against each other. Shapes with the same z are
+/// drawn in the order of their draw calls, with passive shapes first.
+///
+/// ## Cameras and targets
+///
+/// The `Canvas` resource draws on the window, as seen from the 2D camera by
+/// default. The camera can be changed between draw calls with
+/// [`set_camera`](Canvas::set_camera), e.g. to draw world-space shapes and a
+/// UI overlay in the same frame. HUD elements that must not follow any
+/// camera can be drawn in pixels from a corner of the window after switching
+/// to a screen space with [`set_space`](Canvas::set_space).
+///
+/// Other canvases can be added as components to entities with a
+/// [`CanvasTexture`](crate::CanvasTexture) to draw into a texture.
+///
+/// ## Clipping
+///
+/// Shapes can be kept inside a rectangle with [`clip`](Canvas::clip), e.g. to
+/// stop the content of a scrollable panel from overflowing its frame. Like
+/// the transform, the clipping region is part of the saved state:
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{common_shapes::Circle, Canvas, DrawMode};
+///
+/// fn panel_system(mut canvas: ResMut<Canvas>, time: Res<Time>) {
+///     let scroll = time.seconds_since_startup() as f32 * 10.0 % 200.0;
+///
+///     canvas
+///         .save()
+///         .clip(Vec2::new(-100.0, -100.0), Vec2::new(100.0, 100.0))
+///         .translate(Vec2::new(0.0, scroll));
+///     for i in 0..10 {
+///         let item = Circle {
+///             center: Vec2::new(0.0, -40.0 * i as f32),
+///             radius: 15.0,
+///         };
+///         canvas.draw(&item, DrawMode::fill_simple(), Color::TEAL);
+///     }
+///     canvas.restore();
+/// }
+/// ```
 pub struct Canvas {
-    pub(crate) vertex_buffers: BufferPair,
-    fill_tess: FillTessellator,
-    stroke_tess: StrokeTessellator,
+    immediate: DrawList,
+    passive: BTreeMap<PassiveHandle, DrawList>,
+    next_passive_handle: u64,
+    state: DrawState,
+    saved_states: Vec<DrawState>,
+    tessellator: Tessellator,
 }
 
 impl Canvas {
+    /// Creates a canvas that shows its shapes as seen from the camera with the
+    /// given name, unless [`set_camera`](Canvas::set_camera) is called.
+    pub fn with_camera(camera: impl Into<Cow<'static, str>>) -> Self {
+        let mut canvas = Self::default();
+        canvas.set_camera(camera);
+        canvas
+    }
+
     pub fn draw(
         &mut self,
         geometry: &impl Geometry,
         draw_mode: DrawMode,
-        color: Color,
+        paint: impl Into<Paint>,
     ) -> &mut Self {
-        let path = geometry.generate_path();
+        self.tessellator.tessellate(
+            geometry,
+            draw_mode,
+            &paint.into(),
+            &self.state,
+            &mut self.immediate,
+        );
 
-        match draw_mode {
-            DrawMode::Fill(ref options) => self.fill(&path, options, color),
-            DrawMode::Stroke(ref options) => self.stroke(&path, options, color),
+        self
+    }
+
+    /// Draws a [`CanvasText`] by filling its glyphs with the nonzero fill rule.
+    pub fn draw_text(&mut self, text: &CanvasText, paint: impl Into<Paint>) -> &mut Self {
+        self.draw(text, DrawMode::Fill(FillOptions::non_zero()), paint)
+    }
+
+    /// Draws every shape of an SVG document, in order.
+    #[cfg(feature = "svg")]
+    pub fn draw_svg(&mut self, document: &crate::SvgDocument) -> &mut Self {
+        for shape in &document.shapes {
+            self.draw(&shape.path, shape.draw_mode, shape.color);
+        }
+
+        self
+    }
+
+    /// Draws a shape after moving it by `transform`, which is applied before
+    /// the current transform of the canvas.
+    pub fn draw_transformed(
+        &mut self,
+        geometry: &impl Geometry,
+        draw_mode: DrawMode,
+        paint: impl Into<Paint>,
+        transform: Transform2d,
+    ) -> &mut Self {
+        let state = DrawState {
+            transform: transform.then(self.state.transform),
+            ..self.state.clone()
+        };
+        self.tessellator.tessellate(
+            geometry,
+            draw_mode,
+            &paint.into(),
+            &state,
+            &mut self.immediate,
+        );
+
+        self
+    }
+
+    /// Pushes the current drawing state, made of the current transform,
+    /// z-coordinate, feather width, camera, coordinate space and clipping
+    /// region, on a stack.
+    pub fn save(&mut self) -> &mut Self {
+        self.saved_states.push(self.state.clone());
+        self
+    }
+
+    /// Pops the drawing state last pushed by [`save`](Canvas::save) and makes
+    /// it current. Does nothing if the stack is empty.
+    pub fn restore(&mut self) -> &mut Self {
+        if let Some(state) = self.saved_states.pop() {
+            self.state = state;
+        }
+        self
+    }
+
+    pub fn current_transform(&self) -> Transform2d {
+        self.state.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Transform2d) -> &mut Self {
+        self.state.transform = transform;
+        self
+    }
+
+    pub fn reset_transform(&mut self) -> &mut Self {
+        self.set_transform(Transform2d::identity())
+    }
+
+    /// Applies `transform` to the shapes drawn from now on, before the current
+    /// transform.
+    pub fn apply_transform(&mut self, transform: Transform2d) -> &mut Self {
+        self.set_transform(transform.then(self.state.transform))
+    }
+
+    pub fn translate(&mut self, translation: Vec2) -> &mut Self {
+        self.apply_transform(Transform2d::from_translation(translation))
+    }
+
+    /// Rotates the shapes drawn from now on counterclockwise around the
+    /// origin. The angle is expressed in radians.
+    pub fn rotate(&mut self, angle: f32) -> &mut Self {
+        self.apply_transform(Transform2d::from_rotation(angle))
+    }
+
+    pub fn scale(&mut self, scale: Vec2) -> &mut Self {
+        self.apply_transform(Transform2d::from_scale(scale))
+    }
+
+    /// Skews the shapes drawn from now on. See
+    /// [`Transform2d::from_skew`].
+    pub fn skew(&mut self, skew: Vec2) -> &mut Self {
+        self.apply_transform(Transform2d::from_skew(skew))
+    }
+
+    pub fn current_z(&self) -> f32 {
+        self.state.z
+    }
+
+    /// Sets the z-coordinate of the shapes drawn from now on.
+    pub fn set_z(&mut self, z: f32) -> &mut Self {
+        self.state.z = z;
+        self
+    }
+
+    pub fn current_feather(&self) -> f32 {
+        self.state.feather
+    }
+
+    /// Anti-aliases the shapes drawn from now on by surrounding them with a
+    /// fringe of the given width, fading to transparent. A width of `0.0`,
+    /// the default, disables it.
+    ///
+    /// The width is measured in the coordinate system of the shapes after
+    /// the current transform, so with the default 2D camera and in world
+    /// space a width of `1.0` corresponds to one pixel. Unlike MSAA, this
+    /// doesn't need a multisampled render target, but shapes appear slightly
+    /// larger and their overlapping edges may show seams.
+    pub fn set_feather(&mut self, width: f32) -> &mut Self {
+        self.state.feather = width.max(0.0);
+        self
+    }
+
+    /// The name of the camera used to show the shapes drawn from now on.
+    pub fn current_camera(&self) -> &str {
+        &self.state.render_state.camera
+    }
+
+    /// Shows the shapes drawn from now on as seen from the camera with the
+    /// given name.
+    ///
+    /// Shapes drawn through a camera that doesn't exist are not rendered.
+    /// Cameras other than the ones managed by Bevy, like `"Camera2d"` and
+    /// `"CameraUi"`, must be registered with a
+    /// [`CanvasCameraPlugin`](crate::CanvasCameraPlugin).
+    pub fn set_camera(&mut self, camera: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.state.render_state.camera = camera.into();
+        self
+    }
+
+    pub fn current_space(&self) -> CoordinateSpace {
+        self.state.render_state.space
+    }
+
+    /// Sets the coordinate system of the shapes drawn from now on. See
+    /// [`CoordinateSpace`].
+    pub fn set_space(&mut self, space: CoordinateSpace) -> &mut Self {
+        self.state.render_state.space = space;
+        self
+    }
+
+    /// Clips the shapes drawn from now on to the rectangle going from `min`
+    /// to `max`, intersected with the current clipping region. The region can
+    /// only be enlarged again by [`restore`](Canvas::restore).
+    ///
+    /// The rectangle is moved by the current transform, and stays in the
+    /// current coordinate system even if the camera or the space are changed
+    /// later. Clipping is done by the GPU with a scissor rectangle, so rotated
+    /// and skewed rectangles are replaced by their axis-aligned bounding box.
+    pub fn clip(&mut self, min: Vec2, max: Vec2) -> &mut Self {
+        let transform = self.state.transform;
+        let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+        let (mut min, mut max) = (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY));
+        for &corner in &corners {
+            let corner = transform.transform_point(corner);
+            min = min.min(corner);
+            max = max.max(corner);
         }
 
+        let RenderState {
+            camera,
+            space,
+            clips,
+            ..
+        } = &mut self.state.render_state;
+        match clips
+            .iter_mut()
+            .find(|clip| clip.camera == *camera && clip.space == *space)
+        {
+            Some(clip) => {
+                clip.min = clip.min.max(min);
+                clip.max = clip.max.min(max);
+            }
+            None => clips.push(ClipRect {
+                min,
+                max,
+                camera: camera.clone(),
+                space: *space,
+            }),
+        }
         self
     }
 
-    fn fill(&mut self, path: &Path, options: &FillOptions, color: Color) {
-        let mut buffers_builder =
-            BuffersBuilder::new(&mut self.vertex_buffers, VertexConstructor { color });
+    /// Draws a shape in the passive layer, where it stays until it is
+    /// replaced or removed.
+    ///
+    /// ## Usage
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_canvas::{common_shapes::Circle, Canvas, DrawMode, PassiveHandle};
+    ///
+    /// struct Background(PassiveHandle);
+    ///
+    /// // Run this system only once, e.g. as a startup system.
+    /// fn setup_system(mut commands: Commands, mut canvas: ResMut<Canvas>) {
+    ///     let circle = Circle {
+    ///         center: Vec2::ZERO,
+    ///         radius: 300.0,
+    ///     };
+    ///     let handle = canvas.draw_passive(&circle, DrawMode::fill_simple(), Color::GRAY);
+    ///
+    ///     // Keep the handle around to update the shape later.
+    ///     commands.insert_resource(Background(handle));
+    /// }
+    /// ```
+    pub fn draw_passive(
+        &mut self,
+        geometry: &impl Geometry,
+        draw_mode: DrawMode,
+        paint: impl Into<Paint>,
+    ) -> PassiveHandle {
+        let handle = PassiveHandle(self.next_passive_handle);
+        self.next_passive_handle += 1;
+
+        let mut draw_list = DrawList::default();
+        self.tessellator.tessellate(
+            geometry,
+            draw_mode,
+            &paint.into(),
+            &self.state,
+            &mut draw_list,
+        );
+        self.passive.insert(handle, draw_list);
+
+        handle
+    }
+
+    /// Replaces the shape identified by `handle` in the passive layer.
+    ///
+    /// The shape keeps its position in the drawing order. The current transform
+    /// and z-coordinate are applied to the new shape. Returns `false` if the
+    /// handle does not refer to a shape of the passive layer.
+    pub fn replace_passive(
+        &mut self,
+        handle: PassiveHandle,
+        geometry: &impl Geometry,
+        draw_mode: DrawMode,
+        paint: impl Into<Paint>,
+    ) -> bool {
+        let tessellator = &mut self.tessellator;
+        if let Some(draw_list) = self.passive.get_mut(&handle) {
+            draw_list.clear();
+            tessellator.tessellate(geometry, draw_mode, &paint.into(), &self.state, draw_list);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes the shape identified by `handle` from the passive layer.
+    ///
+    /// Returns `false` if the handle does not refer to a shape of the passive
+    /// layer.
+    pub fn remove_passive(&mut self, handle: PassiveHandle) -> bool {
+        self.passive.remove(&handle).is_some()
+    }
+
+    /// Removes every shape from the passive layer.
+    pub fn clear_passive(&mut self) {
+        self.passive.clear();
+    }
+
+    /// Moves the geometry of the current frame into `output`, sorted by
+    /// z-coordinate.
+    ///
+    /// At the same z, passive shapes come first, in the order they were drawn,
+    /// followed by the shapes drawn in immediate mode, which are then
+    /// discarded.
+    ///
+    /// Returns the time spent tessellating the shapes drawn since the last
+    /// flush.
+    pub(crate) fn flush(&mut self, output: &mut CanvasGeometry) -> Duration {
+        output.clear();
+
+        let mut batches = self
+            .passive
+            .values()
+            .chain(std::iter::once(&self.immediate))
+            .flat_map(|draw_list| draw_list.batches.iter())
+            .collect::<Vec<_>>();
+        // The sort is stable, so the drawing order is kept for equal z.
+        batches.sort_by(|a, b| a.z.total_cmp(&b.z));
+        for batch in batches {
+            output.append(&batch.buffers, &batch.render_state);
+        }
+
+        self.immediate.clear();
+        std::mem::take(&mut self.tessellator.elapsed)
+    }
+}
+
+impl Default for Canvas {
+    fn default() -> Self {
+        Self {
+            immediate: DrawList::default(),
+            passive: BTreeMap::new(),
+            next_passive_handle: 0,
+            state: DrawState::default(),
+            saved_states: Vec::new(),
+            tessellator: Tessellator::default(),
+        }
+    }
+}
+
+/// Identifies a shape drawn in the passive layer of a [`Canvas`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PassiveHandle(u64);
+
+/// The part of the state of a [`Canvas`] that can be saved and restored.
+#[derive(Clone, Default)]
+struct DrawState {
+    transform: Transform2d,
+    z: f32,
+    /// The width of the anti-aliasing fringe. There's no fringe if it's `0.0`.
+    feather: f32,
+    render_state: RenderState,
+}
+
+impl DrawState {
+    fn vertex_constructor<'a>(
+        &self,
+        paint: &'a Paint,
+        to_uv: Transform2d,
+    ) -> VertexConstructor<'a> {
+        VertexConstructor {
+            paint,
+            z: self.z,
+            to_local: self.transform.inverse().unwrap_or_default(),
+            to_uv,
+        }
+    }
+
+    /// Returns the render state of the shapes drawn with `paint`.
+    fn render_state_with(&self, paint: &Paint) -> RenderState {
+        RenderState {
+            // A strong handle keeps the texture loaded as long as a passive
+            // shape uses it.
+            texture: paint.texture().cloned(),
+            gradient: paint.gradient(),
+            ..self.render_state.clone()
+        }
+    }
+}
+
+/// Tessellated geometry, split in batches of shapes sharing the same
+/// z-coordinate and render state.
+#[derive(Default)]
+struct DrawList {
+    batches: Vec<Batch>,
+}
+
+impl DrawList {
+    /// Returns the buffers where a shape drawn at `z` with `render_state` must
+    /// be appended.
+    ///
+    /// Consecutive shapes with the same z and render state share a batch.
+    fn batch(&mut self, z: f32, render_state: &RenderState) -> &mut BufferPair {
+        let is_new_batch = self.batches.last().map_or(true, |batch| {
+            batch.z != z || batch.render_state != *render_state
+        });
+        if is_new_batch {
+            self.batches.push(Batch {
+                z,
+                render_state: render_state.clone(),
+                buffers: BufferPair::new(),
+            });
+        }
+
+        &mut self.batches.last_mut().unwrap().buffers
+    }
+
+    fn clear(&mut self) {
+        self.batches.clear();
+    }
+}
+
+struct Batch {
+    z: f32,
+    render_state: RenderState,
+    buffers: BufferPair,
+}
+
+/// Turns geometries into triangle meshes.
+struct Tessellator {
+    fill_tess: FillTessellator,
+    stroke_tess: StrokeTessellator,
+    /// The time spent tessellating since the last flush of the canvas.
+    elapsed: Duration,
+}
+
+impl Tessellator {
+    fn tessellate(
+        &mut self,
+        geometry: &impl Geometry,
+        draw_mode: DrawMode,
+        paint: &Paint,
+        state: &DrawState,
+        output: &mut DrawList,
+    ) {
+        let start = Instant::now();
+        let mut path = geometry.generate_path();
+        let to_uv = paint.uv_transform(&path, &state.transform);
+        if !state.transform.is_identity() {
+            path = transform_path(&path, &state.transform);
+        }
+
+        let vertex_constructor = state.vertex_constructor(paint, to_uv);
+        let render_state = state.render_state_with(paint);
+        let z = state.z;
+        let feather = state.feather;
+        match draw_mode {
+            DrawMode::Fill(ref options) => self.fill(
+                &path,
+                options,
+                vertex_constructor,
+                feather,
+                output.batch(z, &render_state),
+            ),
+            DrawMode::Stroke(ref options) => self.stroke(
+                &path,
+                options,
+                vertex_constructor,
+                feather,
+                output.batch(z, &render_state),
+            ),
+            DrawMode::Outlined {
+                ref fill,
+                ref stroke,
+                outline_color,
+            } => {
+                // The outline is appended after the fill, so it's drawn on top.
+                self.fill(
+                    &path,
+                    fill,
+                    vertex_constructor,
+                    feather,
+                    output.batch(z, &render_state),
+                );
+                let outline_paint = Paint::Color(outline_color);
+                self.stroke(
+                    &path,
+                    stroke,
+                    state.vertex_constructor(&outline_paint, Transform2d::identity()),
+                    feather,
+                    output.batch(z, &state.render_state_with(&outline_paint)),
+                );
+            }
+            DrawMode::VariableStroke(ref options) => {
+                let outline = variable_stroke_outline(&path, options);
+                let fill_options = FillOptions::non_zero().with_tolerance(options.tolerance);
+                self.fill(
+                    &outline,
+                    &fill_options,
+                    vertex_constructor,
+                    feather,
+                    output.batch(z, &render_state),
+                )
+            }
+        }
+
+        self.elapsed += start.elapsed();
+    }
+
+    fn fill(
+        &mut self,
+        path: &Path,
+        options: &FillOptions,
+        vertex_constructor: VertexConstructor,
+        feather: f32,
+        output: &mut BufferPair,
+    ) {
+        let first_index = output.indices.len();
+        let mut buffers_builder = BuffersBuilder::new(output, vertex_constructor);
         if let Err(e) = self
             .fill_tess
             .tessellate_path(path, options, &mut buffers_builder)
         {
             error!("FillTessellator error: {:?}", e);
         }
+        if feather > 0.0 {
+            add_fringe(output, first_index, feather);
+        }
     }
 
-    fn stroke(&mut self, path: &Path, options: &StrokeOptions, color: Color) {
-        let mut buffers_builder =
-            BuffersBuilder::new(&mut self.vertex_buffers, VertexConstructor { color });
+    fn stroke(
+        &mut self,
+        path: &Path,
+        options: &StrokeOptions,
+        vertex_constructor: VertexConstructor,
+        feather: f32,
+        output: &mut BufferPair,
+    ) {
+        let first_index = output.indices.len();
+        let mut buffers_builder = BuffersBuilder::new(output, vertex_constructor);
         if let Err(e) = self
             .stroke_tess
             .tessellate_path(path, options, &mut buffers_builder)
         {
             error!("StrokeTessellator error: {:?}", e);
         }
+        if feather > 0.0 {
+            add_fringe(output, first_index, feather);
+        }
     }
 }
 
-impl Default for Canvas {
+/// Applies `transform` to the points of `path`, keeping its custom attributes
+/// untouched.
+fn transform_path(path: &Path, transform: &Transform2d) -> Path {
+    if path.num_attributes() == 0 {
+        return path.transformed(&transform.0);
+    }
+
+    let p = |point| transform.0.transform_point(point);
+    let mut b = Path::builder_with_attributes(path.num_attributes());
+    for event in path.id_iter() {
+        match event {
+            IdEvent::Begin { at } => {
+                b.begin(p(path[at]), path.attributes(at));
+            }
+            IdEvent::Line { to, .. } => {
+                b.line_to(p(path[to]), path.attributes(to));
+            }
+            IdEvent::Quadratic { ctrl, to, .. } => {
+                b.quadratic_bezier_to(p(path[ctrl]), p(path[to]), path.attributes(to));
+            }
+            IdEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => {
+                b.cubic_bezier_to(
+                    p(path[ctrl1]),
+                    p(path[ctrl2]),
+                    p(path[to]),
+                    path.attributes(to),
+                );
+            }
+            IdEvent::End { close, .. } => b.end(close),
+        }
+    }
+
+    b.build()
+}
+
+impl Default for Tessellator {
     fn default() -> Self {
         Self {
-            vertex_buffers: BufferPair::new(),
             fill_tess: FillTessellator::new(),
             stroke_tess: StrokeTessellator::new(),
+            elapsed: Duration::default(),
         }
     }
 }
@@ -129,6 +768,12 @@ pub trait Geometry {
     fn generate_path(&self) -> Path;
 }
 
+impl Geometry for Path {
+    fn generate_path(&self) -> Path {
+        self.clone()
+    }
+}
+
 /// Determines how a shape is tessellated (i.e. transformed from a parametric
 /// representation to a triangle mesh).
 #[derive(Clone, Copy)]
@@ -137,6 +782,18 @@ pub enum DrawMode {
     Fill(FillOptions),
     /// The shape is tessellated using a stroke operation.
     Stroke(StrokeOptions),
+    /// The shape is tessellated using both a fill and a stroke operation.
+    ///
+    /// The fill uses the paint passed to the draw call, while the outline is
+    /// drawn on top of it using `outline_color`.
+    Outlined {
+        fill: FillOptions,
+        stroke: StrokeOptions,
+        outline_color: Color,
+    },
+    /// The shape is tessellated using a stroke whose width varies along the
+    /// path.
+    VariableStroke(VariableStrokeOptions),
 }
 
 impl DrawMode {
@@ -147,4 +804,12 @@ impl DrawMode {
     pub fn fill_simple() -> Self {
         Self::Fill(FillOptions::default())
     }
+
+    pub fn outlined_1px(outline_color: Color) -> Self {
+        Self::Outlined {
+            fill: FillOptions::default(),
+            stroke: StrokeOptions::default(),
+            outline_color,
+        }
+    }
 }
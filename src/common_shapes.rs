@@ -1,18 +1,21 @@
-// TODO: Add rotation to these structs.
-// TODO: Add rectangle with rounded borders.
-
 //! A convenient collection of shapes.
 //!
 //! These shapes are provided to help users draw basic shapes without needing to
 //! implement the [`Geometry`](crate::Geometry) trait themselves.
+//!
+//! Shapes that have a `rotation` field are rotated counterclockwise by that
+//! angle, in radians, around their reference point.
 
 use bevy::math::Vec2;
 use tess::{
-    math::{point, Angle, Point, Rect, Size},
-    path::{path::Builder, traits::PathBuilder, Polygon as LyonPolygon, Winding},
+    math::{point, Angle, Point, Rect, Size, Transform},
+    path::{
+        builder::BorderRadii, path::Builder, traits::PathBuilder as _, Path,
+        Polygon as LyonPolygon, Winding,
+    },
 };
 
-use crate::{render::types::Conversion, Geometry};
+use crate::{render::types::Conversion, Geometry, PathBuilder};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RectangleAnchor {
@@ -29,6 +32,23 @@ impl Default for RectangleAnchor {
     }
 }
 
+impl RectangleAnchor {
+    /// Returns the bottom-left corner of a rectangle with the given extents,
+    /// whose anchor point is located at `origin`.
+    fn bottom_left(&self, origin: Vec2, extents: Vec2) -> Point {
+        let mut bottom_left = match self {
+            RectangleAnchor::Center => Point::new(-extents.x / 2.0, -extents.y / 2.0),
+            RectangleAnchor::BottomLeft => Point::new(0.0, 0.0),
+            RectangleAnchor::BottomRight => Point::new(-extents.x, 0.0),
+            RectangleAnchor::TopRight => Point::new(-extents.x, -extents.y),
+            RectangleAnchor::TopLeft => Point::new(0.0, -extents.y),
+        };
+        bottom_left.x += origin.x;
+        bottom_left.y += origin.y;
+        bottom_left
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rectangle {
     /// Reference point of the shape.
@@ -38,6 +58,8 @@ pub struct Rectangle {
     /// Part of the shape located at the
     /// `origin`.
     pub anchor_point: RectangleAnchor,
+    /// Rotation around the `origin`.
+    pub rotation: f32,
 }
 
 impl Default for Rectangle {
@@ -46,6 +68,7 @@ impl Default for Rectangle {
             origin: Vec2::ZERO,
             extents: Vec2::ONE,
             anchor_point: RectangleAnchor::default(),
+            rotation: 0.0,
         }
     }
 }
@@ -53,21 +76,89 @@ impl Default for Rectangle {
 impl Geometry for Rectangle {
     fn generate_path(&self) -> tess::path::Path {
         let mut b = Builder::new();
-        let mut origin = match self.anchor_point {
-            RectangleAnchor::Center => Point::new(-self.extents.x / 2.0, -self.extents.y / 2.0),
-            RectangleAnchor::BottomLeft => Point::new(0.0, 0.0),
-            RectangleAnchor::BottomRight => Point::new(-self.extents.x, 0.0),
-            RectangleAnchor::TopRight => Point::new(-self.extents.x, -self.extents.y),
-            RectangleAnchor::TopLeft => Point::new(0.0, -self.extents.y),
-        };
-        origin.x += self.origin.x;
-        origin.y += self.origin.y;
+        let origin = self.anchor_point.bottom_left(self.origin, self.extents);
 
         b.add_rectangle(
             &Rect::new(origin, Size::new(self.extents.x, self.extents.y)),
             Winding::Positive,
         );
-        b.build()
+        rotate(b.build(), self.origin, self.rotation)
+    }
+}
+
+/// The radii of the corners of a [`RoundedRectangle`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CornerRadii {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+impl CornerRadii {
+    /// Creates corner radii that are all equal to `radius`.
+    pub fn uniform(radius: f32) -> Self {
+        Self {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+}
+
+impl From<f32> for CornerRadii {
+    fn from(radius: f32) -> Self {
+        Self::uniform(radius)
+    }
+}
+
+/// A rectangle with rounded corners.
+///
+/// Radii that are too large for the rectangle are reduced to fit it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundedRectangle {
+    /// Reference point of the shape.
+    pub origin: Vec2,
+    /// Width and height.
+    pub extents: Vec2,
+    /// Part of the shape located at the
+    /// `origin`.
+    pub anchor_point: RectangleAnchor,
+    pub radii: CornerRadii,
+    /// Rotation around the `origin`.
+    pub rotation: f32,
+}
+
+impl Default for RoundedRectangle {
+    fn default() -> Self {
+        Self {
+            origin: Vec2::ZERO,
+            extents: Vec2::ONE,
+            anchor_point: RectangleAnchor::default(),
+            radii: CornerRadii::default(),
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Geometry for RoundedRectangle {
+    fn generate_path(&self) -> tess::path::Path {
+        let mut b = Builder::new();
+        let origin = self.anchor_point.bottom_left(self.origin, self.extents);
+
+        // Lyon names the corners as if the y-axis pointed down.
+        b.add_rounded_rectangle(
+            &Rect::new(origin, Size::new(self.extents.x, self.extents.y)),
+            &BorderRadii {
+                top_left: self.radii.bottom_left,
+                top_right: self.radii.bottom_right,
+                bottom_left: self.radii.top_left,
+                bottom_right: self.radii.top_right,
+            },
+            Winding::Positive,
+        );
+        rotate(b.build(), self.origin, self.rotation)
     }
 }
 
@@ -98,6 +189,8 @@ impl Geometry for Circle {
 pub struct Ellipse {
     pub center: Vec2,
     pub radii: Vec2,
+    /// Rotation around the `center`.
+    pub rotation: f32,
 }
 
 impl Default for Ellipse {
@@ -105,6 +198,7 @@ impl Default for Ellipse {
         Self {
             center: Vec2::ZERO,
             radii: Vec2::ONE,
+            rotation: 0.0,
         }
     }
 }
@@ -115,13 +209,131 @@ impl Geometry for Ellipse {
         b.add_ellipse(
             self.center.to_point(),
             self.radii.to_vector(),
-            Angle::zero(),
+            Angle::radians(self.rotation),
             Winding::Positive,
         );
         b.build()
     }
 }
 
+/// An open arc of a circle, going counterclockwise from `start_angle` by
+/// `sweep_angle`. Angles are in radians, starting from the x-axis.
+///
+/// Since it's an open shape, it's meant to be stroked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arc {
+    pub center: Vec2,
+    pub radius: f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32,
+}
+
+impl Default for Arc {
+    fn default() -> Self {
+        Self {
+            center: Vec2::ZERO,
+            radius: 1.0,
+            start_angle: 0.0,
+            sweep_angle: std::f32::consts::PI,
+        }
+    }
+}
+
+impl Geometry for Arc {
+    fn generate_path(&self) -> Path {
+        let mut b = PathBuilder::new();
+        b.move_to(point_on_circle(self.center, self.radius, self.start_angle));
+        b.arc(self.center, Vec2::splat(self.radius), self.sweep_angle, 0.0);
+        b.build()
+    }
+}
+
+/// A slice of a circle, bounded by two radii and the arc between them, like
+/// the slice of a pie chart. Angles are in radians, see [`Arc`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sector {
+    pub center: Vec2,
+    pub radius: f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32,
+}
+
+impl Default for Sector {
+    fn default() -> Self {
+        Self {
+            center: Vec2::ZERO,
+            radius: 1.0,
+            start_angle: 0.0,
+            sweep_angle: std::f32::consts::FRAC_PI_2,
+        }
+    }
+}
+
+impl Geometry for Sector {
+    fn generate_path(&self) -> Path {
+        let mut b = PathBuilder::new();
+        b.move_to(self.center);
+        b.line_to(point_on_circle(self.center, self.radius, self.start_angle));
+        b.arc(self.center, Vec2::splat(self.radius), self.sweep_angle, 0.0);
+        b.close();
+        b.build()
+    }
+}
+
+/// The region between two concentric circles, optionally limited to the
+/// segment between two angles. Angles are in radians, see [`Arc`].
+///
+/// A `sweep_angle` of a full turn gives a whole ring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Annulus {
+    pub center: Vec2,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32,
+}
+
+impl Default for Annulus {
+    fn default() -> Self {
+        Self {
+            center: Vec2::ZERO,
+            inner_radius: 0.5,
+            outer_radius: 1.0,
+            start_angle: 0.0,
+            sweep_angle: 2.0 * std::f32::consts::PI,
+        }
+    }
+}
+
+impl Geometry for Annulus {
+    fn generate_path(&self) -> Path {
+        let end_angle = self.start_angle + self.sweep_angle;
+
+        // Goes along the outer arc, then back along the inner one.
+        let mut b = PathBuilder::new();
+        b.move_to(point_on_circle(
+            self.center,
+            self.outer_radius,
+            self.start_angle,
+        ));
+        b.arc(
+            self.center,
+            Vec2::splat(self.outer_radius),
+            self.sweep_angle,
+            0.0,
+        );
+        b.line_to(point_on_circle(self.center, self.inner_radius, end_angle));
+        b.arc(
+            self.center,
+            Vec2::splat(self.inner_radius),
+            -self.sweep_angle,
+            0.0,
+        );
+        b.close();
+        b.build()
+    }
+}
+
 /// Describes a polygon or a polyline.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Polygon {
@@ -132,6 +344,8 @@ pub struct Polygon {
     /// Determines if the shape will be closed (a polygon) or remain open (a
     /// polyline).
     pub closed: bool,
+    /// Rotation around the `origin`.
+    pub rotation: f32,
 }
 
 impl Default for Polygon {
@@ -140,6 +354,7 @@ impl Default for Polygon {
             origin: Vec2::ZERO,
             points: Vec::new(),
             closed: true,
+            rotation: 0.0,
         }
     }
 }
@@ -150,7 +365,7 @@ impl Geometry for Polygon {
         let points = self
             .points
             .iter()
-            .map(|p| (*p + self.origin).to_point())
+            .map(|&p| (p + self.origin).to_point())
             .collect::<Vec<Point>>();
         let polygon: LyonPolygon<Point> = LyonPolygon {
             points: points.as_slice(),
@@ -158,7 +373,7 @@ impl Geometry for Polygon {
         };
 
         b.add_polygon(polygon);
-        b.build()
+        rotate(b.build(), self.origin, self.rotation)
     }
 }
 
@@ -181,6 +396,8 @@ pub struct RegularPolygon {
     pub sides: usize,
     /// The radius, apothem or side length of the polygon.
     pub feature: RegularPolygonFeature,
+    /// Rotation around the `center`.
+    pub rotation: f32,
 }
 
 impl RegularPolygon {
@@ -202,6 +419,7 @@ impl Default for RegularPolygon {
             center: Vec2::ZERO,
             sides: 3,
             feature: RegularPolygonFeature::Radius(1.0),
+            rotation: 0.0,
         }
     }
 }
@@ -211,7 +429,8 @@ impl Geometry for RegularPolygon {
         // -- Implementation details **PLEASE KEEP UPDATED** --
         // - `step`: angle between two vertices.
         // - `internal`: internal angle of the polygon.
-        // - `offset`: bias to make the shape lay flat on a line parallel to the x-axis.
+        // - `offset`: bias to make the shape lay flat on a line parallel to the x-axis,
+        //   plus the rotation of the shape.
 
         use std::f32::consts::PI;
         assert!(self.sides > 2, "Polygons must have at least 3 sides");
@@ -220,7 +439,7 @@ impl Geometry for RegularPolygon {
         let n = self.sides as f32;
         let radius = self.radius();
         let internal = (n - 2.0) * PI / n;
-        let offset = -internal / 2.0;
+        let offset = -internal / 2.0 + self.rotation;
 
         let mut points = Vec::with_capacity(self.sides);
         let step = 2.0 * PI / n;
@@ -241,6 +460,209 @@ impl Geometry for RegularPolygon {
     }
 }
 
+/// A star with `points` tips, alternating between the outer and the inner
+/// radius.
+///
+/// Without rotation, the first tip points up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Star {
+    pub center: Vec2,
+    /// The number of tips.
+    pub points: usize,
+    /// The distance of the tips from the center.
+    pub outer_radius: f32,
+    /// The distance of the inner vertices from the center.
+    pub inner_radius: f32,
+    /// Rotation around the `center`.
+    pub rotation: f32,
+}
+
+impl Default for Star {
+    fn default() -> Self {
+        Self {
+            center: Vec2::ZERO,
+            points: 5,
+            outer_radius: 1.0,
+            inner_radius: 0.5,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Geometry for Star {
+    fn generate_path(&self) -> Path {
+        use std::f32::consts::{FRAC_PI_2, PI};
+        assert!(self.points > 1, "Stars must have at least 2 points");
+        let mut b = Builder::new();
+
+        let step = PI / self.points as f32;
+        let points = (0..2 * self.points)
+            .map(|i| {
+                let radius = if i % 2 == 0 {
+                    self.outer_radius
+                } else {
+                    self.inner_radius
+                };
+                let angle = (i as f32).mul_add(step, FRAC_PI_2 + self.rotation);
+                point_on_circle(self.center, radius, angle).to_point()
+            })
+            .collect::<Vec<Point>>();
+
+        b.add_polygon(LyonPolygon {
+            points: points.as_slice(),
+            closed: true,
+        });
+        b.build()
+    }
+}
+
+/// A plus-shaped cross, made of two perpendicular bars. Rotate it by a
+/// quarter of a turn to get an x-shaped cross.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cross {
+    pub center: Vec2,
+    /// The length of each bar.
+    pub extent: f32,
+    /// The width of each bar.
+    pub thickness: f32,
+    /// Rotation around the `center`.
+    pub rotation: f32,
+}
+
+impl Default for Cross {
+    fn default() -> Self {
+        Self {
+            center: Vec2::ZERO,
+            extent: 1.0,
+            thickness: 0.25,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Geometry for Cross {
+    fn generate_path(&self) -> Path {
+        let mut b = Builder::new();
+        let (h, t) = (self.extent / 2.0, self.thickness / 2.0);
+        let points = [
+            (t, h),
+            (-t, h),
+            (-t, t),
+            (-h, t),
+            (-h, -t),
+            (-t, -t),
+            (-t, -h),
+            (t, -h),
+            (t, -t),
+            (h, -t),
+            (h, t),
+            (t, t),
+        ]
+        .iter()
+        .map(|&(x, y)| point(x + self.center.x, y + self.center.y))
+        .collect::<Vec<Point>>();
+
+        b.add_polygon(LyonPolygon {
+            points: points.as_slice(),
+            closed: true,
+        });
+        rotate(b.build(), self.center, self.rotation)
+    }
+}
+
+/// The shape drawn at an end of an [`Arrow`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrowHeadStyle {
+    /// The line just ends.
+    None,
+    /// Two short lines forming a V.
+    Open,
+    /// A closed triangle.
+    Closed,
+}
+
+/// A line with an arrow head at either end.
+///
+/// Like [`Line`], it is meant to be stroked. Closed heads are closed
+/// sub-paths, so they can be filled with an outlined
+/// [`DrawMode`](crate::DrawMode).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arrow {
+    pub start: Vec2,
+    /// The point where the arrow points to.
+    pub end: Vec2,
+    /// The style of the head at the `end`.
+    pub head: ArrowHeadStyle,
+    /// The style of the head at the `start`.
+    pub tail: ArrowHeadStyle,
+    /// The length of the heads, along the line.
+    pub head_length: f32,
+    /// The width of the heads, across the line.
+    pub head_width: f32,
+}
+
+impl Default for Arrow {
+    fn default() -> Self {
+        Self {
+            start: Vec2::ZERO,
+            end: Vec2::X,
+            head: ArrowHeadStyle::Open,
+            tail: ArrowHeadStyle::None,
+            head_length: 0.25,
+            head_width: 0.25,
+        }
+    }
+}
+
+impl Arrow {
+    /// Adds a head with the given style pointing to `tip`, and returns the
+    /// point where the line must end.
+    fn add_head(&self, b: &mut Builder, style: ArrowHeadStyle, tip: Vec2, direction: Vec2) -> Vec2 {
+        let base = tip - direction * self.head_length;
+        let side = direction.perp() * self.head_width / 2.0;
+        match style {
+            ArrowHeadStyle::None => tip,
+            ArrowHeadStyle::Open => {
+                b.add_polygon(LyonPolygon {
+                    points: &[
+                        (base + side).to_point(),
+                        tip.to_point(),
+                        (base - side).to_point(),
+                    ],
+                    closed: false,
+                });
+                tip
+            }
+            ArrowHeadStyle::Closed => {
+                b.add_polygon(LyonPolygon {
+                    points: &[
+                        (base + side).to_point(),
+                        tip.to_point(),
+                        (base - side).to_point(),
+                    ],
+                    closed: true,
+                });
+                base
+            }
+        }
+    }
+}
+
+impl Geometry for Arrow {
+    fn generate_path(&self) -> Path {
+        let mut b = Builder::new();
+        let direction = (self.end - self.start).normalize_or_zero();
+
+        let line_end = self.add_head(&mut b, self.head, self.end, direction);
+        let line_start = self.add_head(&mut b, self.tail, self.start, -direction);
+        b.add_polygon(LyonPolygon {
+            points: &[line_start.to_point(), line_end.to_point()],
+            closed: false,
+        });
+        b.build()
+    }
+}
+
 /// A line segment described by its endpoints.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Line(pub Vec2, pub Vec2);
@@ -255,3 +677,21 @@ impl Geometry for Line {
         b.build()
     }
 }
+
+/// Rotates `path` by `angle` radians around `pivot`.
+fn rotate(path: Path, pivot: Vec2, angle: f32) -> Path {
+    if angle == 0.0 {
+        return path;
+    }
+
+    let transform = Transform::translation(-pivot.x, -pivot.y)
+        .then_rotate(Angle::radians(angle))
+        .then_translate(pivot.to_vector());
+    path.transformed(&transform)
+}
+
+/// Returns the point of a circle at the given angle, in radians.
+fn point_on_circle(center: Vec2, radius: f32, angle: f32) -> Vec2 {
+    let (sin, cos) = angle.sin_cos();
+    center + radius * Vec2::new(cos, sin)
+}
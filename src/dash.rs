@@ -0,0 +1,272 @@
+use tess::{
+    math::Point,
+    path::{path::Builder, traits::PathBuilder, Path, PathEvent},
+    StrokeOptions,
+};
+
+use crate::Geometry;
+
+/// The lengths of the dashes and gaps of a [`Dashed`] shape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DashPattern {
+    /// The lengths of dashes and gaps, alternating and starting with a dash.
+    /// Like in SVG, a list with an odd number of lengths is repeated twice.
+    pub dashes: Vec<f32>,
+    /// The distance along the pattern where each sub-path starts.
+    pub offset: f32,
+}
+
+impl DashPattern {
+    pub fn new(dashes: Vec<f32>) -> Self {
+        Self {
+            dashes,
+            offset: 0.0,
+        }
+    }
+
+    /// Creates a pattern of dots placed `spacing` units apart.
+    ///
+    /// Dots are zero-length dashes, so they are only visible when stroked
+    /// with round or square line caps.
+    pub fn dotted(spacing: f32) -> Self {
+        Self::new(vec![0.0, spacing])
+    }
+
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// A shape whose outline is split into dashes, meant to be stroked.
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{
+///     common_shapes::Rectangle, Canvas, DashPattern, Dashed, DrawMode, LineCap, StrokeOptions,
+/// };
+///
+/// fn marquee_system(mut canvas: ResMut<Canvas>, time: Res<Time>) {
+///     let selection = Rectangle {
+///         extents: Vec2::new(200.0, 100.0),
+///         ..Default::default()
+///     };
+///     let pattern = DashPattern::new(vec![8.0, 4.0])
+///         .with_offset(time.seconds_since_startup() as f32 * 20.0);
+///
+///     canvas.draw(
+///         &Dashed::new(selection, pattern),
+///         DrawMode::stroke_1px(),
+///         Color::WHITE,
+///     );
+///
+///     let trajectory = Dashed::new(
+///         bevy_canvas::common_shapes::Line(Vec2::ZERO, Vec2::new(300.0, 200.0)),
+///         DashPattern::dotted(10.0),
+///     );
+///     canvas.draw(
+///         &trajectory,
+///         DrawMode::Stroke(
+///             StrokeOptions::default()
+///                 .with_line_width(4.0)
+///                 .with_line_cap(LineCap::Round),
+///         ),
+///         Color::YELLOW,
+///     );
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dashed<G: Geometry> {
+    pub geometry: G,
+    pub pattern: DashPattern,
+    /// The maximum distance between the curves of the shape and the line
+    /// segments they are approximated with before being split.
+    pub tolerance: f32,
+}
+
+impl<G: Geometry> Dashed<G> {
+    pub fn new(geometry: G, pattern: DashPattern) -> Self {
+        Self {
+            geometry,
+            pattern,
+            tolerance: StrokeOptions::DEFAULT_TOLERANCE,
+        }
+    }
+}
+
+impl<G: Geometry> Geometry for Dashed<G> {
+    fn generate_path(&self) -> Path {
+        let path = self.geometry.generate_path();
+        let mut dashes = self.pattern.dashes.clone();
+        if dashes.len() % 2 == 1 {
+            dashes.extend(self.pattern.dashes.iter().copied());
+        }
+        let total: f32 = dashes.iter().sum();
+        if dashes.iter().any(|&d| d < 0.0) || total <= 0.0 {
+            return path;
+        }
+
+        let mut dasher = Dasher {
+            builder: Builder::new(),
+            dashes: &dashes,
+            index: 0,
+            remaining: 0.0,
+        };
+        for event in path.iter().flattened(self.tolerance) {
+            match event {
+                PathEvent::Begin { at } => dasher.begin(at, self.pattern.offset.rem_euclid(total)),
+                PathEvent::Line { from, to } => dasher.line(from, to),
+                PathEvent::End { last, first, close } => {
+                    if close {
+                        dasher.line(last, first);
+                    }
+                    dasher.end();
+                }
+                _ => {}
+            }
+        }
+
+        dasher.builder.build()
+    }
+}
+
+/// Splits the flattened sub-paths of a shape into dashes.
+struct Dasher<'a> {
+    builder: Builder,
+    dashes: &'a [f32],
+    /// The current entry of `dashes`. Even entries are dashes.
+    index: usize,
+    /// The length left to the end of the current entry.
+    remaining: f32,
+}
+
+impl Dasher<'_> {
+    fn is_dash(&self) -> bool {
+        self.index % 2 == 0
+    }
+
+    /// Starts a sub-path at `at`, moving `offset` units along the pattern.
+    fn begin(&mut self, at: Point, mut offset: f32) {
+        self.index = 0;
+        while offset > self.dashes[self.index] {
+            offset -= self.dashes[self.index];
+            self.index = (self.index + 1) % self.dashes.len();
+        }
+        self.remaining = self.dashes[self.index] - offset;
+
+        if self.is_dash() {
+            self.builder.begin(at);
+        }
+    }
+
+    fn line(&mut self, from: Point, to: Point) {
+        let length = (to - from).length();
+        let mut position = 0.0;
+        while length - position > self.remaining {
+            position += self.remaining;
+            let point = from.lerp(to, position / length);
+            if self.is_dash() {
+                self.builder.line_to(point);
+                self.builder.end(false);
+            } else {
+                self.builder.begin(point);
+            }
+            self.index = (self.index + 1) % self.dashes.len();
+            self.remaining = self.dashes[self.index];
+        }
+
+        self.remaining -= length - position;
+        if self.is_dash() {
+            self.builder.line_to(to);
+        }
+    }
+
+    fn end(&mut self) {
+        if self.is_dash() {
+            self.builder.end(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+    use tess::math::point;
+
+    use super::*;
+    use crate::common_shapes::Line;
+
+    /// Dashes a horizontal line of length 10 and returns the start and end x
+    /// of each dash.
+    fn dashes(pattern: DashPattern) -> Vec<(f32, f32)> {
+        let line = Line(Vec2::ZERO, Vec2::new(10.0, 0.0));
+        let mut dashes = Vec::new();
+        for event in Dashed::new(line, pattern).generate_path().iter() {
+            match event {
+                PathEvent::Begin { at } => dashes.push((at.x, at.x)),
+                PathEvent::Line { to, .. } => dashes.last_mut().unwrap().1 = to.x,
+                PathEvent::End { last, .. } => assert_eq!(last.y, 0.0),
+                event => panic!("unexpected event {:?}", event),
+            }
+        }
+        dashes
+    }
+
+    fn assert_dashes(actual: Vec<(f32, f32)>, expected: &[(f32, f32)]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a.0 - e.0).abs() < 1e-5 && (a.1 - e.1).abs() < 1e-5,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn dashes_alternate_with_gaps() {
+        let dashes = dashes(DashPattern::new(vec![2.0, 3.0]));
+        assert_dashes(dashes, &[(0.0, 2.0), (5.0, 7.0)]);
+    }
+
+    #[test]
+    fn offset_moves_along_the_pattern() {
+        let dashes = dashes(DashPattern::new(vec![2.0, 3.0]).with_offset(1.0));
+        assert_dashes(dashes, &[(0.0, 1.0), (4.0, 6.0), (9.0, 10.0)]);
+    }
+
+    #[test]
+    fn odd_pattern_is_repeated_twice() {
+        let dashes = dashes(DashPattern::new(vec![2.0]));
+        assert_dashes(dashes, &[(0.0, 2.0), (4.0, 6.0), (8.0, 10.0)]);
+    }
+
+    #[test]
+    fn dots_are_zero_length_sub_paths() {
+        let dashes = dashes(DashPattern::dotted(5.0));
+        assert_dashes(dashes, &[(0.0, 0.0), (5.0, 5.0)]);
+    }
+
+    #[test]
+    fn closed_sub_paths_are_dashed_back_to_their_start() {
+        let mut builder = Builder::new();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(4.0, 0.0));
+        builder.end(true);
+        let dashed = Dashed::new(builder.build(), DashPattern::new(vec![3.0, 3.0])).generate_path();
+        let ends = dashed
+            .iter()
+            .filter_map(|event| match event {
+                PathEvent::Line { to, .. } => Some(to),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // The closing segment goes from 4 back to 0, so the second dash
+        // starts at 2.
+        assert_eq!(ends.len(), 2);
+        assert!((ends[0] - point(3.0, 0.0)).length() < 1e-5);
+        assert!((ends[1] - point(0.0, 0.0)).length() < 1e-5);
+    }
+}
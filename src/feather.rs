@@ -0,0 +1,170 @@
+use bevy::{math::Vec2, utils::HashMap};
+
+use crate::render::types::{BufferPair, Vertex};
+
+/// The maximum ratio between the distance of an outer vertex of the fringe
+/// from its edge and the width of the fringe, reached at sharp corners.
+const MITER_LIMIT: f32 = 4.0;
+
+/// Adds a fringe of the given width around the triangles of `buffers` that
+/// come after `first_index`, fading from the color of their edges to
+/// transparent.
+///
+/// The fringe is built along the boundary edges, the ones belonging to a
+/// single triangle, and extends outwards.
+pub(crate) fn add_fringe(buffers: &mut BufferPair, first_index: usize, width: f32) {
+    let position = |vertices: &[Vertex], i: u32| {
+        let [x, y, _] = vertices[i as usize].pos;
+        Vec2::new(x, y)
+    };
+
+    // Counts the triangles sharing each edge, storing the normal that points
+    // out of the last of them.
+    let mut edges: HashMap<(u32, u32), (u32, Vec2)> = HashMap::default();
+    for triangle in buffers.indices[first_index..].chunks_exact(3) {
+        let (a, b, c) = (
+            position(&buffers.vertices, triangle[0]),
+            position(&buffers.vertices, triangle[1]),
+            position(&buffers.vertices, triangle[2]),
+        );
+        // Degenerate triangles have no inside, so they are ignored.
+        let area = (b - a).perp_dot(c - a);
+        if area.abs() <= f32::EPSILON {
+            continue;
+        }
+
+        for k in 0..3 {
+            let (from, to) = (triangle[k], triangle[(k + 1) % 3]);
+            let d = position(&buffers.vertices, to) - position(&buffers.vertices, from);
+            // The normal on the right of the edge points out of
+            // counterclockwise triangles.
+            let normal = Vec2::new(d.y, -d.x).normalize() * area.signum();
+            let entry = edges
+                .entry((from.min(to), from.max(to)))
+                .or_insert((0, normal));
+            entry.0 += 1;
+            entry.1 = normal;
+        }
+    }
+
+    // Sums the normals of the boundary edges around each vertex.
+    let mut boundary = Vec::new();
+    let mut vertex_normals: HashMap<u32, (Vec2, Vec2)> = HashMap::default();
+    for triangle in buffers.indices[first_index..].chunks_exact(3) {
+        for k in 0..3 {
+            let (from, to) = (triangle[k], triangle[(k + 1) % 3]);
+            let (count, normal) = match edges.get(&(from.min(to), from.max(to))) {
+                Some(&edge) => edge,
+                None => continue,
+            };
+            if count != 1 {
+                continue;
+            }
+
+            boundary.push((from, to));
+            for &i in &[from, to] {
+                let entry = vertex_normals.entry(i).or_insert((Vec2::ZERO, normal));
+                entry.0 += normal;
+            }
+        }
+    }
+
+    // Adds a transparent copy of each boundary vertex, moved outwards.
+    let mut outer_vertices: HashMap<u32, u32> = HashMap::default();
+    for &(from, to) in &boundary {
+        for &i in &[from, to] {
+            if outer_vertices.contains_key(&i) {
+                continue;
+            }
+
+            let (sum, normal) = vertex_normals[&i];
+            let direction = if sum.length_squared() > 1e-6 {
+                sum.normalize()
+            } else {
+                normal
+            };
+            // The offset gets longer as the corner gets sharper. Vertices
+            // shared by more than two boundary edges, where shapes touch at a
+            // point, can have their summed normal point inwards, so they
+            // are moved along a single edge instead.
+            let dot = direction.dot(normal);
+            let offset = if dot > 1e-3 {
+                direction * (1.0 / dot).min(MITER_LIMIT)
+            } else {
+                normal
+            };
+
+            let inner = buffers.vertices[i as usize];
+            let [x, y, z] = inner.pos;
+            let [r, g, b, _] = inner.color;
+            let outer = Vec2::new(x, y) + offset * width;
+            outer_vertices.insert(i, buffers.vertices.len() as u32);
+            buffers.vertices.push(Vertex {
+                pos: [outer.x, outer.y, z],
+                color: [r, g, b, 0.0],
+                uv: inner.uv,
+            });
+        }
+    }
+
+    for (from, to) in boundary {
+        let (outer_from, outer_to) = (outer_vertices[&from], outer_vertices[&to]);
+        buffers
+            .indices
+            .extend_from_slice(&[from, to, outer_to, from, outer_to, outer_from]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contains(triangle: &[Vec2], p: Vec2) -> bool {
+        let side = |a: Vec2, b: Vec2| (b - a).perp_dot(p - a);
+        let sides = [
+            side(triangle[0], triangle[1]),
+            side(triangle[1], triangle[2]),
+            side(triangle[2], triangle[0]),
+        ];
+        sides.iter().all(|&s| s > 0.0) || sides.iter().all(|&s| s < 0.0)
+    }
+
+    #[test]
+    fn fringe_of_triangles_touching_at_a_vertex_stays_outside() {
+        let positions = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(-0.5, -1.2),
+            Vec2::new(-2.0, -0.9),
+            Vec2::new(0.4, 1.5),
+            Vec2::new(1.3, 0.0),
+        ];
+        let mut buffers = BufferPair::new();
+        buffers.vertices.extend(positions.iter().map(|p| Vertex {
+            pos: [p.x, p.y, 0.0],
+            color: [1.0; 4],
+            uv: [0.0; 2],
+        }));
+        buffers.indices.extend_from_slice(&[0, 1, 2, 0, 3, 4]);
+
+        let width = 0.1;
+        add_fringe(&mut buffers, 0, width);
+
+        let triangles = [
+            [positions[0], positions[1], positions[2]],
+            [positions[0], positions[3], positions[4]],
+        ];
+        let outer_vertices = &buffers.vertices[positions.len()..];
+        assert_eq!(outer_vertices.len(), positions.len());
+        for vertex in outer_vertices {
+            let p = Vec2::new(vertex.pos[0], vertex.pos[1]);
+            assert!(p.is_finite());
+            assert_eq!(vertex.color[3], 0.0);
+            assert!(triangles.iter().all(|triangle| !contains(triangle, p)));
+            let nearest = positions
+                .iter()
+                .map(|&q| (p - q).length())
+                .fold(f32::INFINITY, f32::min);
+            assert!(nearest <= width * MITER_LIMIT + 1e-5);
+        }
+    }
+}
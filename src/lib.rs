@@ -30,17 +30,54 @@
 //! ## Usage
 //!
 //! For the common usage guide, see the [plugin documentation](CanvasPlugin).
+//!
+//! ## Features
+//!
+//! - `svg`: parses SVG path data into shapes with `SvgPath`, and loads `.svg`
+//!   files as `SvgDocument` assets.
 
-use bevy::app::{App, Plugin};
+use std::borrow::Cow;
+
+#[cfg(feature = "svg")]
+use bevy::asset::AddAsset;
+use bevy::{
+    app::{App, Plugin},
+    ecs::system::IntoSystem,
+    render::RenderStage,
+};
 
 mod canvas;
 pub mod common_shapes;
+mod dash;
+mod feather;
+mod offscreen;
+mod paint;
 mod path;
 mod render;
+mod space;
+mod stats;
+mod stroke;
+#[cfg(feature = "svg")]
+mod svg;
+mod text;
+mod transform;
 
-pub use canvas::{Canvas, DrawMode, Geometry};
-pub use path::PathBuilder;
+pub use ab_glyph::InvalidFont;
+pub use canvas::{Canvas, DrawMode, Geometry, PassiveHandle};
+pub use dash::{DashPattern, Dashed};
+pub use offscreen::{CanvasTexture, OffscreenCanvasBundle};
+pub use paint::{
+    ColorStop, ConicGradient, LinearGradient, Paint, RadialGradient, TexturePaint, UvMapping,
+};
+pub use path::{ColoredPathBuilder, PathBuilder};
+pub use space::{CoordinateSpace, ScreenOrigin};
+pub use stats::CanvasStats;
+pub use stroke::{StrokeWidth, VariableStrokeOptions};
+#[cfg(feature = "svg")]
+pub use svg::{SvgDocument, SvgLoader, SvgPath, SvgPathError, SvgShape};
 pub use tess::{path::Path, FillOptions, FillRule, LineCap, LineJoin, Orientation, StrokeOptions};
+pub use text::{CanvasFont, CanvasText, HorizontalAlign, VerticalAlign};
+pub use transform::Transform2d;
 
 /// A Bevy `Plugin` that gives the ability to directly draw 2D shapes from a
 /// system.
@@ -51,6 +88,13 @@ pub use tess::{path::Path, FillOptions, FillRule, LineCap, LineJoin, Orientation
 /// context exposes a [`draw`](Canvas::draw) method that can accept any struct
 /// that implements the [`Geometry`] trait.
 ///
+/// Shapes drawn on the window are anti-aliased with the sample count of the
+/// `Msaa` resource, and like the pipelines of Bevy, the canvas pipeline is
+/// recreated when it changes. The canvas draws on the attachments of the main
+/// pass, which Bevy creates with the sample count of `Msaa` when the plugins
+/// are added: changing it later only works if they are replaced with matching
+/// ones.
+///
 /// ### Example
 /// ```
 /// use bevy::prelude::*;
@@ -67,6 +111,7 @@ pub use tess::{path::Path, FillOptions, FillRule, LineCap, LineJoin, Orientation
 ///         center: Vec2::ZERO,
 ///         sides: 6,
 ///         feature: RegularPolygonFeature::Radius(100.0),
+///         ..Default::default()
 ///     };
 ///
 ///     canvas.draw(&my_shape, DrawMode::fill_simple(), Color::MIDNIGHT_BLUE);
@@ -77,8 +122,80 @@ pub struct CanvasPlugin;
 
 impl Plugin for CanvasPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Canvas::default());
+        app.insert_resource(Canvas::default())
+            .init_resource::<CanvasStats>()
+            .init_resource::<render::CanvasSampleCount>()
+            .add_system_to_stage(
+                RenderStage::RenderGraphSystems,
+                render::update_canvas_sample_count.system(),
+            );
+        #[cfg(feature = "svg")]
+        app.add_asset::<SvgDocument>()
+            .init_asset_loader::<SvgLoader>();
 
         render::setup_canvas_node(&mut app.world);
     }
 }
+
+/// A Bevy `Plugin` that lets canvases draw through a custom camera.
+///
+/// Cameras are identified by name, like the `"Camera2d"` used by default. This
+/// plugin registers the camera with the given name as an active camera, so
+/// that the [`Camera`](bevy::render::camera::Camera) component with the same
+/// name gets its view-projection matrix computed every frame, before the
+/// canvas is drawn.
+///
+/// Cameras already managed by Bevy are only ordered before the canvas. The
+/// canvas does it by itself for the 2D, 3D and UI cameras whose plugins are
+/// added before the [`CanvasPlugin`]; this plugin is only needed when they
+/// are added later.
+///
+/// Must be added after the [`CanvasPlugin`].
+///
+/// ### Example
+/// ```
+/// use bevy::{prelude::*, render::camera::Camera};
+/// use bevy_canvas::{common_shapes::Circle, Canvas, CanvasCameraPlugin, DrawMode};
+///
+/// const MINIMAP_CAMERA: &str = "MinimapCamera";
+///
+/// fn build(app: &mut App) {
+///     app.add_plugin(CanvasCameraPlugin::new(MINIMAP_CAMERA));
+/// }
+///
+/// fn setup_system(mut commands: Commands) {
+///     let mut camera = OrthographicCameraBundle::new_2d();
+///     camera.camera = Camera {
+///         name: Some(MINIMAP_CAMERA.to_string()),
+///         ..Default::default()
+///     };
+///     commands.spawn_bundle(camera);
+/// }
+///
+/// fn draw_system(mut canvas: ResMut<Canvas>) {
+///     let circle = Circle {
+///         center: Vec2::ZERO,
+///         radius: 10.0,
+///     };
+///
+///     canvas.set_camera(MINIMAP_CAMERA);
+///     canvas.draw(&circle, DrawMode::fill_simple(), Color::RED);
+/// }
+/// ```
+pub struct CanvasCameraPlugin {
+    camera: Cow<'static, str>,
+}
+
+impl CanvasCameraPlugin {
+    pub fn new(camera: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            camera: camera.into(),
+        }
+    }
+}
+
+impl Plugin for CanvasCameraPlugin {
+    fn build(&self, app: &mut App) {
+        render::setup_canvas_camera(&mut app.world, &self.camera);
+    }
+}
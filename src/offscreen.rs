@@ -0,0 +1,118 @@
+use bevy::{
+    asset::{Handle, HandleId},
+    ecs::bundle::Bundle,
+    math::{Mat4, Vec2},
+    render::{color::Color, texture::Texture},
+};
+
+use crate::Canvas;
+
+/// A component that makes the [`Canvas`] of its entity draw into a texture
+/// instead of the window.
+///
+/// The texture is created by the canvas renderer with the given size, and it
+/// can be used like any other texture, e.g. by a sprite material. For this
+/// reason, `texture` must not refer to a texture stored in
+/// `Assets<Texture>`.
+///
+/// Like the main canvas, an offscreen canvas shows the shapes as seen from the
+/// 2D camera, unless another camera is chosen with [`Canvas::set_camera`].
+/// The projection of a camera fits the window, so the texture shows the same
+/// area as the window, stretched if their aspect ratios differ. To show a
+/// given area of world space instead, regardless of the cameras, set the
+/// [`world_view`](CanvasTexture::world_view) of the texture.
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{
+///     common_shapes::Circle, Canvas, CanvasTexture, DrawMode, OffscreenCanvasBundle,
+/// };
+///
+/// struct Minimap;
+///
+/// fn setup_system(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+///     let target =
+///         CanvasTexture::new(256, 256).with_world_view(Vec2::splat(-500.0), Vec2::splat(500.0));
+///
+///     // Show the texture through a sprite...
+///     commands.spawn_bundle(SpriteBundle {
+///         material: materials.add(target.texture.clone().into()),
+///         ..Default::default()
+///     });
+///
+///     // ...and spawn the canvas that draws on it.
+///     commands
+///         .spawn_bundle(OffscreenCanvasBundle::new(target))
+///         .insert(Minimap);
+/// }
+///
+/// fn draw_minimap_system(mut query: Query<&mut Canvas, With<Minimap>>) {
+///     let circle = Circle {
+///         center: Vec2::ZERO,
+///         radius: 50.0,
+///     };
+///
+///     for mut canvas in query.iter_mut() {
+///         canvas.draw(&circle, DrawMode::fill_simple(), Color::RED);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CanvasTexture {
+    pub texture: Handle<Texture>,
+    /// Width of the texture, in pixels.
+    pub width: u32,
+    /// Height of the texture, in pixels.
+    pub height: u32,
+    /// The color of the texture before anything is drawn in the frame.
+    pub clear_color: Color,
+    /// The minimum and maximum corners of the rectangle of world space shown
+    /// by the texture. If `None`, world space is shown through the camera of
+    /// each shape.
+    pub world_view: Option<(Vec2, Vec2)>,
+}
+
+impl CanvasTexture {
+    /// Creates a transparent canvas texture with the given size and a new
+    /// texture handle.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            texture: Handle::weak(HandleId::random::<Texture>()),
+            width,
+            height,
+            clear_color: Color::NONE,
+            world_view: None,
+        }
+    }
+
+    pub fn with_world_view(mut self, min: Vec2, max: Vec2) -> Self {
+        self.world_view = Some((min, max));
+        self
+    }
+
+    /// Returns the projection of the world view on the texture, if any. Like
+    /// the 2D camera, it shows the shapes with z from `-0.1` to `999.9`.
+    pub(crate) fn world_projection(&self) -> Option<Mat4> {
+        let (min, max) = self.world_view?;
+        Some(Mat4::orthographic_rh(
+            min.x, max.x, min.y, max.y, -999.9, 0.1,
+        ))
+    }
+}
+
+/// A bundle for a [`Canvas`] that draws into a texture.
+#[derive(Bundle)]
+pub struct OffscreenCanvasBundle {
+    pub canvas: Canvas,
+    pub target: CanvasTexture,
+}
+
+impl OffscreenCanvasBundle {
+    pub fn new(target: CanvasTexture) -> Self {
+        Self {
+            canvas: Canvas::default(),
+            target,
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use bevy::{
+    asset::Handle,
+    math::{Vec2, Vec4},
+    render::{color::Color, texture::Texture},
+};
+use tess::path::{Path, PathEvent};
+
+use crate::{
+    render::types::{Gradient, MAX_GRADIENT_STOPS},
+    Transform2d,
+};
+
+/// Determines the color of each point of a shape.
+///
+/// Gradients are defined in the coordinate system of the shape, before the
+/// transform of the [`Canvas`](crate::Canvas) is applied, so they follow the
+/// shape when it is moved, rotated or scaled.
+///
+/// Gradients are evaluated for every pixel, so they don't depend on how the
+/// shape is tessellated. Gradients with more than 16 color stops are
+/// resampled to 16 evenly spaced stops.
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{common_shapes::Circle, Canvas, ColorStop, DrawMode, RadialGradient};
+///
+/// fn my_system(mut canvas: ResMut<Canvas>) {
+///     let circle = Circle {
+///         center: Vec2::ZERO,
+///         radius: 100.0,
+///     };
+///     let gradient = RadialGradient {
+///         center: Vec2::ZERO,
+///         radius: 100.0,
+///         stops: vec![
+///             ColorStop::new(0.0, Color::WHITE),
+///             ColorStop::new(1.0, Color::MIDNIGHT_BLUE),
+///         ],
+///     };
+///
+///     canvas.draw(&circle, DrawMode::fill_simple(), gradient);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    /// A single color for the whole shape.
+    Color(Color),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+    ConicGradient(ConicGradient),
+    /// The colors stored at the endpoints of a path built with a
+    /// [`ColoredPathBuilder`](crate::ColoredPathBuilder), interpolated over the
+    /// shape. Shapes without colors are drawn white.
+    VertexColors,
+    Texture(TexturePaint),
+}
+
+impl Paint {
+    /// Returns the linear RGBA color of the vertices of the paint at
+    /// `position`, given the custom attributes of the path interpolated at that
+    /// position. Gradients are white, since they are evaluated per fragment.
+    pub(crate) fn color_at(&self, _position: Vec2, attributes: &[f32]) -> Vec4 {
+        match self {
+            Paint::Color(color) => (*color).into(),
+            Paint::LinearGradient(_) | Paint::RadialGradient(_) | Paint::ConicGradient(_) => {
+                Vec4::ONE
+            }
+            Paint::VertexColors => match *attributes {
+                [r, g, b, a, ..] => Vec4::new(r, g, b, a),
+                _ => Vec4::ONE,
+            },
+            Paint::Texture(texture) => texture.tint.into(),
+        }
+    }
+
+    /// The texture that the colors of the paint are multiplied by, if any.
+    pub(crate) fn texture(&self) -> Option<&Handle<Texture>> {
+        match self {
+            Paint::Texture(texture) => Some(&texture.texture),
+            _ => None,
+        }
+    }
+
+    /// The gradient that the colors of the paint are multiplied by, if any.
+    pub(crate) fn gradient(&self) -> Option<Gradient> {
+        let (kind, stops) = match self {
+            Paint::LinearGradient(gradient) => (1.0, &gradient.stops),
+            Paint::RadialGradient(gradient) => (2.0, &gradient.stops),
+            Paint::ConicGradient(gradient) => (3.0, &gradient.stops),
+            _ => return None,
+        };
+
+        Some(gradient_uniform(kind, stops))
+    }
+
+    /// Returns the transform from the coordinate system of `path` to texture
+    /// coordinates, or to the gradient coordinates read by the fragment
+    /// shader. `transform` is the transform of the canvas the path is drawn
+    /// with.
+    pub(crate) fn uv_transform(&self, path: &Path, transform: &Transform2d) -> Transform2d {
+        match self {
+            Paint::Texture(texture) => texture.mapping.uv_transform(path, transform),
+            Paint::LinearGradient(gradient) => gradient.uv_transform(),
+            Paint::RadialGradient(gradient) => gradient.uv_transform(),
+            Paint::ConicGradient(gradient) => gradient.uv_transform(),
+            _ => Transform2d::identity(),
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Self {
+        Self::Color(color)
+    }
+}
+
+impl From<TexturePaint> for Paint {
+    fn from(texture: TexturePaint) -> Self {
+        Self::Texture(texture)
+    }
+}
+
+impl From<LinearGradient> for Paint {
+    fn from(gradient: LinearGradient) -> Self {
+        Self::LinearGradient(gradient)
+    }
+}
+
+impl From<RadialGradient> for Paint {
+    fn from(gradient: RadialGradient) -> Self {
+        Self::RadialGradient(gradient)
+    }
+}
+
+impl From<ConicGradient> for Paint {
+    fn from(gradient: ConicGradient) -> Self {
+        Self::ConicGradient(gradient)
+    }
+}
+
+/// A color placed along a gradient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    /// Position of the stop along the gradient, from `0.0` to `1.0`.
+    pub offset: f32,
+    pub color: Color,
+}
+
+impl ColorStop {
+    pub fn new(offset: f32, color: Color) -> Self {
+        Self { offset, color }
+    }
+}
+
+/// A gradient that varies along the segment from `start` to `end`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient {
+    pub start: Vec2,
+    pub end: Vec2,
+    /// The colors of the gradient, sorted by increasing offset.
+    pub stops: Vec<ColorStop>,
+}
+
+impl LinearGradient {
+    /// Maps the segment of the gradient to the x-axis, from `0.0` to `1.0`.
+    fn uv_transform(&self) -> Transform2d {
+        let direction = self.end - self.start;
+        let length = direction.length();
+        if length <= 0.0 {
+            return Transform2d::from_scale(Vec2::ZERO);
+        }
+
+        Transform2d::from_translation(-self.start)
+            .then(Transform2d::from_rotation(-direction.y.atan2(direction.x)))
+            .then(Transform2d::from_scale(Vec2::splat(1.0 / length)))
+    }
+}
+
+/// A gradient that varies from the `center` to a circle of the given
+/// `radius`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadialGradient {
+    pub center: Vec2,
+    pub radius: f32,
+    /// The colors of the gradient, sorted by increasing offset.
+    pub stops: Vec<ColorStop>,
+}
+
+impl RadialGradient {
+    /// Maps the circle of the gradient to the unit circle.
+    fn uv_transform(&self) -> Transform2d {
+        if self.radius <= 0.0 {
+            return Transform2d::from_scale(Vec2::ZERO);
+        }
+
+        Transform2d::from_translation(-self.center)
+            .then(Transform2d::from_scale(Vec2::splat(1.0 / self.radius)))
+    }
+}
+
+/// A gradient that varies counterclockwise around the `center`, completing a
+/// full turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConicGradient {
+    pub center: Vec2,
+    /// The angle, in radians, where the gradient starts. An angle of `0.0`
+    /// points in the direction of the x-axis.
+    pub start_angle: f32,
+    /// The colors of the gradient, sorted by increasing offset.
+    pub stops: Vec<ColorStop>,
+}
+
+impl ConicGradient {
+    /// Moves the center of the gradient to the origin, with the start angle
+    /// along the x-axis.
+    fn uv_transform(&self) -> Transform2d {
+        Transform2d::from_translation(-self.center)
+            .then(Transform2d::from_rotation(-self.start_angle))
+    }
+}
+
+/// A paint that fills a shape with a texture.
+///
+/// The texture can be any `Texture` asset, or the texture of an offscreen
+/// canvas. It is sampled with linear filtering and repeated outside of the
+/// `0.0..=1.0` range of texture coordinates.
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{common_shapes::Circle, Canvas, DrawMode, TexturePaint, UvMapping};
+///
+/// struct Portrait(Handle<Texture>);
+///
+/// fn portrait_system(mut canvas: ResMut<Canvas>, portrait: Res<Portrait>) {
+///     let frame = Circle {
+///         center: Vec2::ZERO,
+///         radius: 64.0,
+///     };
+///     let paint = TexturePaint::new(portrait.0.clone(), UvMapping::Stretch);
+///
+///     canvas.draw(&frame, DrawMode::fill_simple(), paint);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TexturePaint {
+    pub texture: Handle<Texture>,
+    pub mapping: UvMapping,
+    /// A color that multiplies the color of the texture.
+    pub tint: Color,
+}
+
+impl TexturePaint {
+    pub fn new(texture: Handle<Texture>, mapping: UvMapping) -> Self {
+        Self {
+            texture,
+            mapping,
+            tint: Color::WHITE,
+        }
+    }
+}
+
+/// Determines how a [`TexturePaint`] is laid out on a shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UvMapping {
+    /// The texture covers the bounding box of the shape.
+    Stretch,
+    /// The texture is repeated, with each copy covering a rectangle of the
+    /// given size, starting from the origin. Tiles are laid out after the
+    /// transform of the [`Canvas`](crate::Canvas) is applied, so they don't
+    /// move, rotate or scale with the shape.
+    ///
+    /// A component of the size equal to zero makes the texture coordinate
+    /// along that axis constant.
+    Tile { size: Vec2 },
+    /// Texture coordinates are computed by transforming the positions of the
+    /// shape, in its coordinate system. The texture covers the unit square,
+    /// with `(0, 0)` at its top-left corner.
+    Transform(Transform2d),
+}
+
+impl UvMapping {
+    fn uv_transform(&self, path: &Path, transform: &Transform2d) -> Transform2d {
+        match *self {
+            UvMapping::Stretch => {
+                let (min, max) = bounding_box(path);
+                let size = (max - min).max(Vec2::splat(f32::EPSILON));
+                Transform2d::from_translation(Vec2::new(-min.x, -max.y))
+                    .then(Transform2d::from_scale(Vec2::new(1.0, -1.0) / size))
+            }
+            UvMapping::Tile { size } => {
+                let inverse = |length: f32| if length != 0.0 { 1.0 / length } else { 0.0 };
+                let scale = Vec2::new(inverse(size.x), -inverse(size.y));
+                transform.then(Transform2d::from_scale(scale))
+            }
+            UvMapping::Transform(transform) => transform,
+        }
+    }
+}
+
+/// Returns the corners of a box containing `path`, with the minimum and
+/// maximum coordinates.
+fn bounding_box(path: &Path) -> (Vec2, Vec2) {
+    let mut min = Vec2::splat(f32::INFINITY);
+    let mut max = Vec2::splat(f32::NEG_INFINITY);
+    let mut add = |p: tess::math::Point| {
+        min = min.min(Vec2::new(p.x, p.y));
+        max = max.max(Vec2::new(p.x, p.y));
+    };
+
+    // Control points are included too: the box may be larger than the shape,
+    // but it's cheap to compute.
+    for event in path.iter() {
+        match event {
+            PathEvent::Begin { at } => add(at),
+            PathEvent::Line { to, .. } => add(to),
+            PathEvent::Quadratic { ctrl, to, .. } => {
+                add(ctrl);
+                add(to);
+            }
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => {
+                add(ctrl1);
+                add(ctrl2);
+                add(to);
+            }
+            PathEvent::End { .. } => {}
+        }
+    }
+
+    if min.x > max.x {
+        (Vec2::ZERO, Vec2::ZERO)
+    } else {
+        (min, max)
+    }
+}
+
+/// Lays out a gradient of the given kind for the fragment shader. If there are
+/// too many stops, the colors are sampled at evenly spaced offsets instead.
+fn gradient_uniform(kind: f32, stops: &[ColorStop]) -> Gradient {
+    let samples = if stops.len() <= MAX_GRADIENT_STOPS {
+        stops
+            .iter()
+            .map(|stop| (stop.offset, Vec4::from(stop.color)))
+            .collect::<Vec<_>>()
+    } else {
+        let first = stops[0].offset;
+        let last = stops[stops.len() - 1].offset;
+        (0..MAX_GRADIENT_STOPS)
+            .map(|i| {
+                let offset = first + (last - first) * i as f32 / (MAX_GRADIENT_STOPS - 1) as f32;
+                (offset, interpolate_stops(stops, offset))
+            })
+            .collect()
+    };
+
+    let mut gradient = Gradient {
+        params: [kind, samples.len() as f32, 0.0, 0.0],
+        offsets: [[0.0; 4]; MAX_GRADIENT_STOPS],
+        colors: [[0.0; 4]; MAX_GRADIENT_STOPS],
+    };
+    for (i, (offset, color)) in samples.into_iter().enumerate() {
+        gradient.offsets[i][0] = offset;
+        gradient.colors[i] = color.into();
+    }
+    gradient
+}
+
+/// Computes the linear RGBA color at the offset `t` of a gradient.
+fn interpolate_stops(stops: &[ColorStop], t: f32) -> Vec4 {
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Vec4::ZERO,
+    };
+
+    if t <= first.offset {
+        return first.color.into();
+    }
+
+    for pair in stops.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if t <= b.offset {
+            let range = b.offset - a.offset;
+            let factor = if range > 0.0 {
+                (t - a.offset) / range
+            } else {
+                1.0
+            };
+
+            return Vec4::from(a.color).lerp(b.color.into(), factor);
+        }
+    }
+
+    last.color.into()
+}
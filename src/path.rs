@@ -1,7 +1,14 @@
-use bevy::math::Vec2;
+use bevy::{
+    math::{Vec2, Vec4},
+    render::color::Color,
+};
 use tess::{
     math::Angle,
-    path::{builder::WithSvg, path::Builder, EndpointId, Path},
+    path::{
+        builder::WithSvg,
+        path::{Builder, BuilderWithAttributes},
+        EndpointId, Path,
+    },
 };
 
 use crate::render::types::Conversion;
@@ -78,3 +85,122 @@ impl Default for PathBuilder {
         Self::new()
     }
 }
+
+/// The number of custom attributes of the paths built by a
+/// [`ColoredPathBuilder`]: the RGBA components of a color.
+pub(crate) const COLOR_ATTRIBUTES: usize = 4;
+
+/// Builder for a Lyon `Path` with a color at each endpoint.
+///
+/// The colors are interpolated over the tessellated shape when it's drawn with
+/// [`Paint::VertexColors`](crate::Paint::VertexColors).
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{Canvas, ColoredPathBuilder, DrawMode, Paint, StrokeOptions};
+///
+/// fn heatmap_system(mut canvas: ResMut<Canvas>) {
+///     let mut b = ColoredPathBuilder::new();
+///     b.move_to(Vec2::new(-200.0, 0.0), Color::BLUE);
+///     b.line_to(Vec2::new(0.0, 100.0), Color::YELLOW);
+///     b.line_to(Vec2::new(200.0, 0.0), Color::RED);
+///     let polyline = b.build();
+///
+///     canvas.draw(
+///         &polyline,
+///         DrawMode::Stroke(StrokeOptions::default().with_line_width(10.0)),
+///         Paint::VertexColors,
+///     );
+/// }
+/// ```
+pub struct ColoredPathBuilder {
+    builder: BuilderWithAttributes,
+    in_sub_path: bool,
+    /// The first endpoint of the last sub-path.
+    sub_path_start: (Vec2, Color),
+}
+
+impl ColoredPathBuilder {
+    pub fn new() -> Self {
+        Self {
+            builder: Path::builder_with_attributes(COLOR_ATTRIBUTES),
+            in_sub_path: false,
+            sub_path_start: (Vec2::ZERO, Color::WHITE),
+        }
+    }
+
+    pub fn build(mut self) -> Path {
+        if self.in_sub_path {
+            self.builder.end(false);
+        }
+        self.builder.build()
+    }
+
+    /// Starts a new sub-path at `to`, ending the current one.
+    pub fn move_to(&mut self, to: Vec2, color: Color) -> EndpointId {
+        if self.in_sub_path {
+            self.builder.end(false);
+        }
+        self.in_sub_path = true;
+        self.sub_path_start = (to, color);
+        self.builder.begin(to.to_point(), &color_attributes(color))
+    }
+
+    pub fn line_to(&mut self, to: Vec2, color: Color) -> EndpointId {
+        self.begin_if_needed();
+        self.builder
+            .line_to(to.to_point(), &color_attributes(color))
+    }
+
+    /// Closes the current sub-path.
+    pub fn close(&mut self) {
+        if self.in_sub_path {
+            self.builder.end(true);
+            self.in_sub_path = false;
+        }
+    }
+
+    pub fn quadratic_bezier_to(&mut self, ctrl: Vec2, to: Vec2, color: Color) -> EndpointId {
+        self.begin_if_needed();
+        self.builder
+            .quadratic_bezier_to(ctrl.to_point(), to.to_point(), &color_attributes(color))
+    }
+
+    pub fn cubic_bezier_to(
+        &mut self,
+        ctrl1: Vec2,
+        ctrl2: Vec2,
+        to: Vec2,
+        color: Color,
+    ) -> EndpointId {
+        self.begin_if_needed();
+        self.builder.cubic_bezier_to(
+            ctrl1.to_point(),
+            ctrl2.to_point(),
+            to.to_point(),
+            &color_attributes(color),
+        )
+    }
+
+    /// Starts a sub-path if none is in progress. Like in SVG paths, it starts
+    /// where the last closed sub-path started, or at the origin in white if
+    /// there's none.
+    fn begin_if_needed(&mut self) {
+        if !self.in_sub_path {
+            let (start, color) = self.sub_path_start;
+            self.move_to(start, color);
+        }
+    }
+}
+
+impl Default for ColoredPathBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stores a color as path attributes, in linear RGBA.
+fn color_attributes(color: Color) -> [f32; COLOR_ATTRIBUTES] {
+    Vec4::from(color).into()
+}
@@ -0,0 +1,134 @@
+use bevy::{
+    core::cast_slice,
+    render::renderer::{
+        BufferId, BufferInfo, BufferMapMode, BufferUsage, RenderContext, RenderResourceContext,
+    },
+};
+
+use super::types::CanvasGeometry;
+
+/// The vertex and index buffers holding the geometry drawn on a render target.
+///
+/// Every render target needs its own buffers, since the passes of a frame are
+/// only submitted after all of them have been recorded.
+#[derive(Debug)]
+pub(crate) struct GeometryBuffers {
+    vertices: GrowableBuffer,
+    indices: GrowableBuffer,
+}
+
+impl Default for GeometryBuffers {
+    fn default() -> Self {
+        Self {
+            vertices: GrowableBuffer::new(BufferUsage::VERTEX),
+            indices: GrowableBuffer::new(BufferUsage::INDEX),
+        }
+    }
+}
+
+impl GeometryBuffers {
+    /// Uploads `geometry` and returns the vertex and index buffers, or `None`
+    /// if nothing was ever uploaded.
+    pub fn write(
+        &mut self,
+        render_context: &mut dyn RenderContext,
+        geometry: &CanvasGeometry,
+    ) -> Option<(BufferId, BufferId)> {
+        let vertices = self.vertices.write(
+            render_context,
+            cast_slice(geometry.buffers.vertices.as_slice()),
+        );
+        let indices = self.indices.write(
+            render_context,
+            cast_slice(geometry.buffers.indices.as_slice()),
+        );
+        vertices.zip(indices)
+    }
+
+    pub fn remove(&self, render_resource_context: &dyn RenderResourceContext) {
+        self.vertices.remove(render_resource_context);
+        self.indices.remove(render_resource_context);
+    }
+}
+
+/// A GPU buffer kept across frames, that is only reallocated when the data
+/// written to it exceeds its capacity.
+///
+/// Data is uploaded through a staging buffer of the same capacity.
+#[derive(Debug)]
+pub(crate) struct GrowableBuffer {
+    usage: BufferUsage,
+    /// The size of both buffers in bytes.
+    capacity: usize,
+    buffers: Option<(BufferId, BufferId)>,
+}
+
+impl GrowableBuffer {
+    pub fn new(usage: BufferUsage) -> Self {
+        Self {
+            usage,
+            capacity: 0,
+            buffers: None,
+        }
+    }
+
+    pub fn remove(&self, render_resource_context: &dyn RenderResourceContext) {
+        if let Some((buffer, staging_buffer)) = self.buffers {
+            render_resource_context.remove_buffer(buffer);
+            render_resource_context.remove_buffer(staging_buffer);
+        }
+    }
+
+    /// Writes `data` at the start of the buffer and returns it. The buffer is
+    /// reallocated with a capacity of the next power of two if `data` doesn't
+    /// fit.
+    ///
+    /// Returns `None` if `data` is empty and no buffer was allocated yet.
+    pub fn write(
+        &mut self,
+        render_context: &mut dyn RenderContext,
+        data: &[u8],
+    ) -> Option<BufferId> {
+        if data.is_empty() {
+            return self.buffers.map(|(buffer, _)| buffer);
+        }
+
+        let render_resources = render_context.resources();
+        let (buffer, staging_buffer) = match self.buffers {
+            Some(buffers) if data.len() <= self.capacity => {
+                render_resources.map_buffer(buffers.1, BufferMapMode::Write);
+                buffers
+            }
+            _ => {
+                if let Some((buffer, staging_buffer)) = self.buffers.take() {
+                    render_resources.remove_buffer(buffer);
+                    render_resources.remove_buffer(staging_buffer);
+                }
+
+                self.capacity = data.len().next_power_of_two();
+                let buffer = render_resources.create_buffer(BufferInfo {
+                    size: self.capacity,
+                    buffer_usage: self.usage | BufferUsage::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                let staging_buffer = render_resources.create_buffer(BufferInfo {
+                    size: self.capacity,
+                    buffer_usage: BufferUsage::MAP_WRITE | BufferUsage::COPY_SRC,
+                    mapped_at_creation: true,
+                });
+                self.buffers = Some((buffer, staging_buffer));
+                (buffer, staging_buffer)
+            }
+        };
+
+        render_resources.write_mapped_buffer(
+            staging_buffer,
+            0..data.len() as u64,
+            &mut |mapped, _| mapped.copy_from_slice(data),
+        );
+        render_resources.unmap_buffer(staging_buffer);
+        render_context.copy_buffer_to_buffer(staging_buffer, 0, buffer, 0, data.len() as u64);
+
+        Some(buffer)
+    }
+}
@@ -2,11 +2,16 @@ use std::mem::size_of;
 
 use bevy::{
     asset::{Assets, Handle, HandleUntyped},
-    core::cast_slice,
-    ecs::world::World,
+    core::{cast_slice, Zeroable},
+    ecs::{
+        system::{Res, ResMut},
+        world::World,
+    },
+    log::warn,
+    math::{Mat4, Vec2},
     reflect::TypeUuid,
     render::{
-        camera::ActiveCameras,
+        camera::{ActiveCameras, Camera},
         pass::{
             LoadOp, Operations, PassDescriptor, RenderPassColorAttachment,
             RenderPassDepthStencilAttachment, TextureAttachment,
@@ -20,51 +25,90 @@ use bevy::{
             VertexBufferLayout, VertexFormat,
         },
         render_graph::{
-            base, Node, RenderGraph, ResourceSlotInfo, ResourceSlots, SlotLabel,
-            WindowSwapChainNode, WindowTextureNode,
+            base::{self, Msaa},
+            CameraNode, Node, RenderGraph, RenderGraphError, ResourceSlotInfo, ResourceSlots,
+            SlotLabel, WindowSwapChainNode, WindowTextureNode,
         },
         renderer::{
-            BindGroup, BufferInfo, BufferUsage, RenderContext, RenderResourceBindings,
-            RenderResourceContext, RenderResourceType,
+            BindGroup, BindGroupId, BufferId, BufferInfo, BufferUsage, RenderContext,
+            RenderResourceBinding, RenderResourceBindings, RenderResourceContext, RenderResourceId,
+            RenderResourceType, SamplerId,
         },
         shader::{Shader, ShaderStage, ShaderStages},
-        texture::TextureFormat,
+        texture::{
+            AddressMode, Extent3d, FilterMode, SamplerDescriptor, Texture, TextureDimension,
+            TextureFormat, TextureSampleType, TextureViewDimension, TEXTURE_ASSET_INDEX,
+        },
     },
+    transform::components::GlobalTransform,
+    utils::{HashMap, HashSet},
+    window::Windows,
 };
-use types::Vertex;
+use types::{Gradient, Vertex, MAX_GRADIENT_STOPS};
 
-use crate::render::types::BufferPair;
+use self::{buffer::GeometryBuffers, offscreen_node::OffscreenCanvasNode};
+use crate::{
+    canvas::Canvas,
+    render::types::{CanvasGeometry, RenderState, Viewport},
+    stats::CanvasStats,
+    CoordinateSpace,
+};
 
+mod buffer;
+mod offscreen_node;
 pub mod types;
 
 pub mod node {
     pub const CANVAS: &str = "bevy_canvas:render:canvas_node";
+    pub const OFFSCREEN_CANVAS: &str = "bevy_canvas:render:offscreen_canvas_node";
 }
 
 pub const CANVAS_PIPELINE_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0xae17f73d2a1827d1);
+/// The pipeline used to draw on the window when MSAA is enabled.
+pub const CANVAS_MULTISAMPLED_PIPELINE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(PipelineDescriptor::TYPE_UUID, 0x5b0e2c94d17a36f8);
+/// A white texture, sampled by the shapes that aren't textured.
+pub const WHITE_TEXTURE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Texture::TYPE_UUID, 0x3c5a1f0e9b7d4e21);
+/// The names of the camera and of the render graph node added by the UI plugin
+/// of Bevy, which the canvas doesn't depend on.
+const CAMERA_UI: &str = "CameraUi";
+const CAMERA_UI_NODE: &str = "camera_ui";
+/// The render graph nodes of the cameras managed by Bevy.
+const BEVY_CAMERA_NODES: [&str; 3] = [base::node::CAMERA_2D, base::node::CAMERA_3D, CAMERA_UI_NODE];
+/// The start of the names of the camera nodes added by the canvas.
+const CANVAS_CAMERA_NODE_PREFIX: &str = "bevy_canvas:render:camera:";
 const COLOR_ATTACHMENT_SLOT: SlotLabel = SlotLabel::Index(0);
 const DEPTH_STENCIL_ATTACHMENT_SLOT: SlotLabel = SlotLabel::Index(1);
+const COLOR_RESOLVE_TARGET_SLOT: SlotLabel = SlotLabel::Index(2);
 const VERTEX_SHADER_SRC: &str = if cfg!(target_arch = "wasm32") {
-    "" // TODO: include_str!("shader.es.vert")
+    include_str!("shader.es.vert")
 } else {
     include_str!("shader.vert")
 };
 const FRAGMENT_SHADER_SRC: &str = if cfg!(target_arch = "wasm32") {
-    "" // TODO: include_str!("shader.es.frag")
+    include_str!("shader.es.frag")
 } else {
     include_str!("shader.frag")
 };
 
 #[derive(Debug)]
 pub struct CanvasNode {
-    input_slots: [ResourceSlotInfo; 2],
-    vertex_buffers: BufferPair,
+    input_slots: Vec<ResourceSlotInfo>,
+    /// The sample count of the attachments, read from [`Msaa`] when the node
+    /// is added to the render graph.
+    sample_count: u32,
+    geometry: CanvasGeometry,
+    buffers: GeometryBuffers,
+    renderer: CanvasRenderer,
 }
 
 impl CanvasNode {
-    fn new() -> Self {
-        let inputs = [
+    /// Creates the node. If `sample_count` is greater than 1, it draws on a
+    /// multisampled color attachment and resolves it to a separate target.
+    fn new(sample_count: u32) -> Self {
+        let mut inputs = vec![
             ResourceSlotInfo::new(
                 "bevy_canvas:render:canvas_node:color_attachment",
                 RenderResourceType::Texture,
@@ -74,10 +118,22 @@ impl CanvasNode {
                 RenderResourceType::Texture,
             ),
         ];
+        if sample_count > 1 {
+            inputs.push(ResourceSlotInfo::new(
+                "bevy_canvas:render:canvas_node:color_resolve_target",
+                RenderResourceType::Texture,
+            ));
+        }
 
         Self {
             input_slots: inputs,
-            vertex_buffers: BufferPair::new(),
+            sample_count,
+            geometry: CanvasGeometry::new(),
+            buffers: GeometryBuffers::default(),
+            renderer: CanvasRenderer {
+                multisampled: sample_count > 1,
+                ..Default::default()
+            },
         }
     }
 }
@@ -88,9 +144,19 @@ impl Node for CanvasNode {
     }
 
     fn prepare(&mut self, world: &mut World) {
-        let mut canvas = world.get_resource_mut::<crate::canvas::Canvas>().unwrap();
-        // TODO: Try optimizing. Make benchmarks. (use mem::swap?)
-        self.vertex_buffers = std::mem::take(&mut canvas.vertex_buffers);
+        let tessellation_time = world
+            .get_resource_mut::<Canvas>()
+            .unwrap()
+            .flush(&mut self.geometry);
+
+        if let Some(mut stats) = world.get_resource_mut::<CanvasStats>() {
+            *stats = CanvasStats {
+                vertices: self.geometry.buffers.vertices.len(),
+                indices: self.geometry.buffers.indices.len(),
+                draw_calls: self.geometry.draw_calls.len(),
+                tessellation_time,
+            };
+        }
     }
 
     fn update(
@@ -100,79 +166,390 @@ impl Node for CanvasNode {
         input: &ResourceSlots,
         _output: &mut ResourceSlots,
     ) {
-        let pipeline = CANVAS_PIPELINE_HANDLE.typed::<PipelineDescriptor>();
-        let render_resource_bindings = world.get_resource::<RenderResourceBindings>().unwrap();
-        let render_resources = render_context.resources();
-        let pipelines = world.get_resource::<Assets<PipelineDescriptor>>().unwrap();
+        // The pass draws on top of the main pass, so it can be skipped if
+        // there's nothing to draw.
+        if self.geometry.draw_calls.is_empty() {
+            return;
+        }
 
-        let active_cameras = world.get_resource::<ActiveCameras>().unwrap();
-        let camera_2d = active_cameras.get("Camera2d").unwrap();
-        let camera_binding = if let Some(binding) = camera_2d.bindings.get("CameraViewProj") {
-            binding.clone()
+        let viewport = world
+            .get_resource::<Windows>()
+            .and_then(|windows| windows.get_primary())
+            .map_or(
+                Viewport {
+                    physical_size: Vec2::ONE,
+                    scale_factor: 1.0,
+                    world_projection: None,
+                },
+                |window| Viewport {
+                    physical_size: Vec2::new(
+                        window.physical_width() as f32,
+                        window.physical_height() as f32,
+                    ),
+                    scale_factor: window.scale_factor() as f32,
+                    world_projection: None,
+                },
+            );
+
+        let pass_descriptor = pass_descriptor(input, self.sample_count);
+        self.renderer.draw(
+            world,
+            render_context,
+            &pass_descriptor,
+            &self.geometry,
+            &mut self.buffers,
+            viewport,
+        );
+    }
+}
+
+/// Draws the geometry of a canvas in a render pass.
+#[derive(Debug, Default)]
+pub(crate) struct CanvasRenderer {
+    /// Cameras that were already reported as missing, to avoid repeating the
+    /// warning every frame.
+    missing_cameras: HashSet<String>,
+    /// Whether the missing camera bind group descriptor was already reported.
+    missing_bind_group_descriptor: bool,
+    /// The sampler used for every texture drawn by the canvas.
+    sampler: Option<SamplerId>,
+    /// Whether to draw with the pipeline at
+    /// [`CANVAS_MULTISAMPLED_PIPELINE_HANDLE`] instead of the one at
+    /// [`CANVAS_PIPELINE_HANDLE`], with a single sample.
+    multisampled: bool,
+}
+
+/// Identifies the view-projection matrix used by a draw call.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum View<'a> {
+    Camera(&'a str),
+    Screen(CoordinateSpace),
+}
+
+impl<'a> View<'a> {
+    fn of(render_state: &'a RenderState) -> Self {
+        match render_state.space {
+            CoordinateSpace::World => View::Camera(&render_state.camera),
+            space => View::Screen(space),
+        }
+    }
+}
+
+impl CanvasRenderer {
+    /// Draws `geometry` in a new render pass on a render target of the given
+    /// size. Each draw call is shown as seen from its camera, and skipped if
+    /// the camera doesn't exist or its texture isn't loaded yet.
+    pub fn draw(
+        &mut self,
+        world: &World,
+        render_context: &mut dyn RenderContext,
+        pass_descriptor: &PassDescriptor,
+        geometry: &CanvasGeometry,
+        buffers: &mut GeometryBuffers,
+        viewport: Viewport,
+    ) {
+        let pipeline = if self.multisampled {
+            CANVAS_MULTISAMPLED_PIPELINE_HANDLE.typed::<PipelineDescriptor>()
         } else {
-            println!("Cannot find camera!");
-            return;
+            CANVAS_PIPELINE_HANDLE.typed::<PipelineDescriptor>()
         };
+        let render_resource_bindings = world.get_resource::<RenderResourceBindings>().unwrap();
+        let pipelines = world.get_resource::<Assets<PipelineDescriptor>>().unwrap();
+        let active_cameras = world.get_resource::<ActiveCameras>().unwrap();
 
-        let camera_bind_group_descriptor = pipelines
+        let layout = pipelines
             .get(pipeline.clone())
             .unwrap()
             .get_layout()
-            .unwrap()
-            .get_bind_group(0)
             .unwrap();
-        let camera_bind_group_id = if render_context
+        let camera_bind_group_descriptor = layout.get_bind_group(0).unwrap();
+        let texture_bind_group_descriptor = layout.get_bind_group(1).unwrap();
+        let gradient_bind_group_descriptor = layout.get_bind_group(2).unwrap();
+        if !render_context
             .resources()
             .bind_group_descriptor_exists(camera_bind_group_descriptor.id)
         {
-            let camera_bind_group = BindGroup::build().add_binding(0, camera_binding).finish();
-            render_resources.create_bind_group(camera_bind_group_descriptor.id, &camera_bind_group);
-            camera_bind_group.id
-        } else {
-            println!("Cannot find camera bind group descriptor!");
+            if !self.missing_bind_group_descriptor {
+                warn!("Cannot find the camera bind group descriptor: the canvas is not drawn.");
+                self.missing_bind_group_descriptor = true;
+            }
             return;
-        };
+        }
+        self.missing_bind_group_descriptor = false;
+
+        // Creates a bind group for every view used by the canvas. Screen
+        // spaces, and world space when the render target has its own
+        // projection, get a uniform buffer with their projection matrix.
+        let render_resources = render_context.resources();
+        let mut view_bind_groups = HashMap::default();
+        let mut projection_buffers = Vec::new();
+        for draw_call in &geometry.draw_calls {
+            let view = View::of(&draw_call.render_state);
+            if view_bind_groups.contains_key(&view) {
+                continue;
+            }
 
-        let sample_count = world
-            .get_resource::<bevy::render::render_graph::base::Msaa>()
-            .map(|msaa| msaa.samples)
-            .unwrap_or(1);
+            let binding = match (view, viewport.world_projection) {
+                (View::Camera(_), Some(projection)) => Some(projection_binding(
+                    render_resources,
+                    projection,
+                    &mut projection_buffers,
+                )),
+                (View::Camera(camera), None) => {
+                    let binding = active_cameras
+                        .get(camera)
+                        .and_then(|camera| camera.bindings.get("CameraViewProj"));
+                    if binding.is_some() {
+                        self.missing_cameras.remove(camera);
+                    } else if self.missing_cameras.insert(camera.to_owned()) {
+                        warn!(
+                            "Cannot find camera \"{}\": the shapes drawn through it are skipped.",
+                            camera
+                        );
+                    }
+                    binding.cloned()
+                }
+                (View::Screen(space), _) => {
+                    let projection = space
+                        .screen_projection(viewport.physical_size, viewport.scale_factor)
+                        .unwrap();
+                    Some(projection_binding(
+                        render_resources,
+                        projection,
+                        &mut projection_buffers,
+                    ))
+                }
+            };
 
-        let index_buffer = render_resources.create_buffer_with_data(
-            BufferInfo {
-                buffer_usage: BufferUsage::INDEX,
-                ..Default::default()
-            },
-            //&self.vertex_buffers.indices.as_bytes(),
-            cast_slice(self.vertex_buffers.indices.as_slice()),
-        );
+            if let Some(binding) = binding {
+                let bind_group = BindGroup::build().add_binding(0, binding).finish();
+                render_resources.create_bind_group(camera_bind_group_descriptor.id, &bind_group);
+                view_bind_groups.insert(view, bind_group.id);
+            }
+        }
 
-        let vertex_buffer = render_resources.create_buffer_with_data(
-            BufferInfo {
-                buffer_usage: BufferUsage::VERTEX,
+        // Creates a bind group for every texture used by the canvas. Shapes
+        // that aren't textured sample a white texture.
+        let sampler = *self.sampler.get_or_insert_with(|| {
+            render_resources.create_sampler(&SamplerDescriptor {
+                address_mode_u: AddressMode::Repeat,
+                address_mode_v: AddressMode::Repeat,
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
                 ..Default::default()
-            },
-            cast_slice(self.vertex_buffers.vertices.as_slice()),
-        );
+            })
+        });
+        let white_texture = WHITE_TEXTURE_HANDLE.typed::<Texture>();
+        let mut texture_bind_groups = HashMap::default();
+        for draw_call in &geometry.draw_calls {
+            let texture = draw_call
+                .render_state
+                .texture
+                .as_ref()
+                .unwrap_or(&white_texture);
+            if texture_bind_groups.contains_key(texture) {
+                continue;
+            }
 
-        let pass_descriptor = pass_descriptor(input, sample_count);
-        render_context.begin_pass(&pass_descriptor, render_resource_bindings, &mut |pass| {
+            if let Some(RenderResourceId::Texture(texture_id)) =
+                render_resources.get_asset_resource(texture, TEXTURE_ASSET_INDEX)
+            {
+                let bind_group = BindGroup::build()
+                    .add_texture(0, texture_id)
+                    .add_sampler(1, sampler)
+                    .finish();
+                render_resources.create_bind_group(texture_bind_group_descriptor.id, &bind_group);
+                texture_bind_groups.insert(texture.clone_weak(), bind_group.id);
+            }
+        }
+
+        // Creates a bind group for every gradient used by the canvas. Shapes
+        // without gradients get one with no stops.
+        let mut gradients: Vec<(Option<&Gradient>, BindGroupId)> = Vec::new();
+        let mut gradient_buffers = Vec::new();
+        let gradient_bind_groups = geometry
+            .draw_calls
+            .iter()
+            .map(|draw_call| {
+                let gradient = draw_call.render_state.gradient.as_ref();
+                if let Some(&(_, id)) = gradients.iter().find(|(g, _)| *g == gradient) {
+                    return id;
+                }
+
+                let uniform = gradient.copied().unwrap_or_else(Gradient::zeroed);
+                let buffer = render_resources.create_buffer_with_data(
+                    BufferInfo {
+                        buffer_usage: BufferUsage::UNIFORM,
+                        ..Default::default()
+                    },
+                    cast_slice(std::slice::from_ref(&uniform)),
+                );
+                gradient_buffers.push(buffer);
+                let bind_group = BindGroup::build()
+                    .add_binding(
+                        0,
+                        RenderResourceBinding::Buffer {
+                            buffer,
+                            range: 0..size_of::<Gradient>() as u64,
+                            dynamic_index: None,
+                        },
+                    )
+                    .finish();
+                render_resources.create_bind_group(gradient_bind_group_descriptor.id, &bind_group);
+                gradients.push((gradient, bind_group.id));
+                bind_group.id
+            })
+            .collect::<Vec<_>>();
+
+        let buffers = buffers.write(render_context, geometry);
+
+        let scissors = geometry
+            .draw_calls
+            .iter()
+            .map(|draw_call| scissor(world, active_cameras, &draw_call.render_state, viewport))
+            .collect::<Vec<_>>();
+
+        render_context.begin_pass(pass_descriptor, render_resource_bindings, &mut |pass| {
             pass.set_pipeline(&pipeline);
-            pass.set_bind_group(
-                0,
-                camera_bind_group_descriptor.id,
-                camera_bind_group_id,
-                None,
-            );
-            pass.set_vertex_buffer(0, vertex_buffer, 0);
-            pass.set_index_buffer(index_buffer, 0, IndexFormat::Uint32);
-            pass.draw_indexed(0..(self.vertex_buffers.indices.len() as u32), 0, 0..1);
+            match buffers {
+                Some((vertex_buffer, index_buffer)) => {
+                    pass.set_vertex_buffer(0, vertex_buffer, 0);
+                    pass.set_index_buffer(index_buffer, 0, IndexFormat::Uint32);
+                }
+                None => return,
+            }
+            for ((draw_call, scissor), &gradient_bind_group_id) in geometry
+                .draw_calls
+                .iter()
+                .zip(&scissors)
+                .zip(&gradient_bind_groups)
+            {
+                let scissor = match scissor {
+                    Some(scissor) => scissor,
+                    None => continue,
+                };
+                let view_bind_group_id =
+                    match view_bind_groups.get(&View::of(&draw_call.render_state)) {
+                        Some(id) => *id,
+                        None => continue,
+                    };
+                let texture = draw_call
+                    .render_state
+                    .texture
+                    .as_ref()
+                    .unwrap_or(&white_texture);
+                let texture_bind_group_id = match texture_bind_groups.get(texture) {
+                    Some(id) => *id,
+                    None => continue,
+                };
+
+                pass.set_scissor_rect(scissor.x, scissor.y, scissor.width, scissor.height);
+                pass.set_bind_group(0, camera_bind_group_descriptor.id, view_bind_group_id, None);
+                pass.set_bind_group(
+                    1,
+                    texture_bind_group_descriptor.id,
+                    texture_bind_group_id,
+                    None,
+                );
+                pass.set_bind_group(
+                    2,
+                    gradient_bind_group_descriptor.id,
+                    gradient_bind_group_id,
+                    None,
+                );
+                pass.draw_indexed(draw_call.indices.clone(), 0, 0..1);
+            }
         });
 
         let render_resources = render_context.resources();
-        render_resources.remove_buffer(vertex_buffer);
-        render_resources.remove_buffer(index_buffer);
+        for buffer in projection_buffers.into_iter().chain(gradient_buffers) {
+            render_resources.remove_buffer(buffer);
+        }
+    }
+}
+
+/// Creates a uniform buffer holding `projection`, adding it to `buffers` to be
+/// removed after drawing, and returns its binding.
+fn projection_binding(
+    render_resources: &dyn RenderResourceContext,
+    projection: Mat4,
+    buffers: &mut Vec<BufferId>,
+) -> RenderResourceBinding {
+    let buffer = render_resources.create_buffer_with_data(
+        BufferInfo {
+            buffer_usage: BufferUsage::UNIFORM,
+            ..Default::default()
+        },
+        cast_slice(&projection.to_cols_array()[..]),
+    );
+    buffers.push(buffer);
+    RenderResourceBinding::Buffer {
+        buffer,
+        range: 0..size_of::<Mat4>() as u64,
+        dynamic_index: None,
+    }
+}
+
+/// A rectangle of the render target in physical pixels, starting from its top
+/// left corner.
+struct Scissor {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Returns the part of the render target that the shapes drawn with
+/// `render_state` are clipped to, or `None` if they can't be seen.
+fn scissor(
+    world: &World,
+    active_cameras: &ActiveCameras,
+    render_state: &RenderState,
+    viewport: Viewport,
+) -> Option<Scissor> {
+    let size = viewport.physical_size;
+    let (mut min, mut max) = (Vec2::ZERO, size);
+    for clip in &render_state.clips {
+        let view_proj = match clip.space {
+            CoordinateSpace::World => match viewport.world_projection {
+                Some(projection) => projection,
+                None => camera_view_proj(world, active_cameras, &clip.camera)?,
+            },
+            space => space.screen_projection(size, viewport.scale_factor)?,
+        };
+
+        // Maps the corners from normalized device coordinates, where y points
+        // up, to pixels, where y points down.
+        let to_pixels = |corner: Vec2| {
+            let ndc = view_proj.transform_point3(corner.extend(0.0));
+            Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) * size / 2.0
+        };
+        let (a, b) = (to_pixels(clip.min), to_pixels(clip.max));
+        min = min.max(a.min(b));
+        max = max.min(a.max(b));
     }
+
+    let min = min.floor();
+    let max = max.ceil();
+    if max.x <= min.x || max.y <= min.y {
+        return None;
+    }
+
+    Some(Scissor {
+        x: min.x as u32,
+        y: min.y as u32,
+        width: (max.x - min.x) as u32,
+        height: (max.y - min.y) as u32,
+    })
+}
+
+/// Returns the view-projection matrix of the camera with the given name, if
+/// it exists.
+fn camera_view_proj(world: &World, active_cameras: &ActiveCameras, name: &str) -> Option<Mat4> {
+    let entity = active_cameras.get(name)?.entity?;
+    let camera = world.get::<Camera>(entity)?;
+    let transform = world.get::<GlobalTransform>(entity)?;
+    Some(camera.projection_matrix * transform.compute_matrix().inverse())
 }
 
 pub(crate) fn setup_canvas_node(world: &mut World) {
@@ -181,26 +558,165 @@ pub(crate) fn setup_canvas_node(world: &mut World) {
         .get_resource_mut::<Assets<PipelineDescriptor>>()
         .unwrap();
     let mut shaders = world.get_resource_mut::<Assets<Shader>>().unwrap();
+    let mut textures = world.get_resource_mut::<Assets<Texture>>().unwrap();
     let mut render_graph = world.get_resource_mut::<RenderGraph>().unwrap();
     let render_resource_context = world
         .get_resource::<Box<dyn RenderResourceContext>>()
         .unwrap();
+    // Like the main pass, the canvas draws on the multisampled color
+    // attachment created by Bevy and resolves it to the swap chain.
+    let sample_count = world.get_resource::<Msaa>().map_or(1, |msaa| msaa.samples);
+    world.get_resource_mut::<CanvasSampleCount>().unwrap().0 = sample_count;
 
-    pipelines.set_untracked(CANVAS_PIPELINE_HANDLE, pipeline_descriptor(&mut *shaders));
+    let descriptor = pipeline_descriptor(&mut *shaders);
+    if sample_count > 1 {
+        pipelines.set_untracked(
+            CANVAS_MULTISAMPLED_PIPELINE_HANDLE,
+            multisampled_pipeline_descriptor(&descriptor, sample_count),
+        );
+    }
+    pipelines.set_untracked(CANVAS_PIPELINE_HANDLE, descriptor);
+    textures.set_untracked(
+        WHITE_TEXTURE_HANDLE,
+        Texture::new_fill(
+            Extent3d::new(1, 1, 1),
+            TextureDimension::D2,
+            &[255, 255, 255, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        ),
+    );
 
-    let pipeline_handle: Handle<PipelineDescriptor> = CANVAS_PIPELINE_HANDLE.typed();
-    let pipeline_descriptor = pipelines.get(pipeline_handle.clone()).unwrap();
-    render_resource_context.create_render_pipeline(pipeline_handle, pipeline_descriptor, &*shaders);
+    for handle in [CANVAS_PIPELINE_HANDLE, CANVAS_MULTISAMPLED_PIPELINE_HANDLE].iter() {
+        let pipeline_handle: Handle<PipelineDescriptor> = handle.clone().typed();
+        if let Some(pipeline_descriptor) = pipelines.get(pipeline_handle.clone()) {
+            render_resource_context.create_render_pipeline(
+                pipeline_handle,
+                pipeline_descriptor,
+                &*shaders,
+            );
+        }
+    }
+
+    add_canvas_node(&mut render_graph, sample_count);
 
-    render_graph.add_node(node::CANVAS, CanvasNode::new());
+    // Offscreen canvases are drawn before the main pass, so that their
+    // textures can be shown in the same frame.
+    render_graph.add_node(node::OFFSCREEN_CANVAS, OffscreenCanvasNode::default());
     render_graph
-        .add_slot_edge(
-            base::node::PRIMARY_SWAP_CHAIN,
-            WindowSwapChainNode::OUT_TEXTURE,
-            node::CANVAS,
-            COLOR_ATTACHMENT_SLOT,
-        )
+        .add_node_edge(node::OFFSCREEN_CANVAS, base::node::MAIN_PASS)
         .unwrap();
+    for camera_node in BEVY_CAMERA_NODES.iter() {
+        add_camera_edges(&mut render_graph, camera_node);
+    }
+}
+
+/// The sample count of the pipeline and attachments of the [`CanvasNode`].
+#[derive(Debug, Default)]
+pub(crate) struct CanvasSampleCount(u32);
+
+/// Recreates the multisampled pipeline and the [`CanvasNode`] with its slot
+/// edges when the sample count of `Msaa` changes.
+///
+/// The canvas draws on the attachments of Bevy, so multisampling can only be
+/// enabled if the render graph has a multisampled color attachment.
+pub(crate) fn update_canvas_sample_count(
+    msaa: Res<Msaa>,
+    mut sample_count: ResMut<CanvasSampleCount>,
+    mut render_graph: ResMut<RenderGraph>,
+    mut pipelines: ResMut<Assets<PipelineDescriptor>>,
+    shaders: Res<Assets<Shader>>,
+    render_resource_context: Res<Box<dyn RenderResourceContext>>,
+) {
+    if !msaa.is_changed() || msaa.samples == sample_count.0 {
+        return;
+    }
+    if msaa.samples > 1
+        && render_graph
+            .get_node_id(base::node::MAIN_SAMPLED_COLOR_ATTACHMENT)
+            .is_err()
+    {
+        warn!(
+            "Cannot draw the canvas with {} samples: the render graph has no multisampled color \
+             attachment.",
+            msaa.samples
+        );
+        return;
+    }
+    sample_count.0 = msaa.samples;
+
+    if msaa.samples > 1 {
+        let descriptor = multisampled_pipeline_descriptor(
+            pipelines.get(CANVAS_PIPELINE_HANDLE.typed_weak()).unwrap(),
+            msaa.samples,
+        );
+        pipelines.set_untracked(CANVAS_MULTISAMPLED_PIPELINE_HANDLE, descriptor);
+        let pipeline_handle = CANVAS_MULTISAMPLED_PIPELINE_HANDLE.typed::<PipelineDescriptor>();
+        render_resource_context.create_render_pipeline(
+            pipeline_handle.clone(),
+            pipelines.get(pipeline_handle).unwrap(),
+            &*shaders,
+        );
+    }
+
+    // Removing the node removes its edges too, so the cameras are ordered
+    // before the new node again.
+    if let Ok(canvas_node) = render_graph.get_node::<CanvasNode>(node::CANVAS) {
+        canvas_node.buffers.remove(&**render_resource_context);
+    }
+    render_graph.remove_node(node::CANVAS).unwrap();
+    add_canvas_node(&mut render_graph, msaa.samples);
+    let camera_nodes = render_graph
+        .iter_nodes()
+        .filter_map(|node| node.name.clone())
+        .filter(|name| {
+            BEVY_CAMERA_NODES.contains(&&**name) || name.starts_with(CANVAS_CAMERA_NODE_PREFIX)
+        })
+        .collect::<Vec<_>>();
+    for camera_node in camera_nodes {
+        add_camera_edges(&mut render_graph, &camera_node);
+    }
+}
+
+fn multisampled_pipeline_descriptor(
+    descriptor: &PipelineDescriptor,
+    sample_count: u32,
+) -> PipelineDescriptor {
+    let mut multisampled_descriptor = descriptor.clone();
+    multisampled_descriptor.multisample.count = sample_count;
+    multisampled_descriptor
+}
+
+/// Adds the [`CanvasNode`] that draws on the window with the given sample
+/// count, after the main pass.
+fn add_canvas_node(render_graph: &mut RenderGraph, sample_count: u32) {
+    render_graph.add_node(node::CANVAS, CanvasNode::new(sample_count));
+    if sample_count > 1 {
+        render_graph
+            .add_slot_edge(
+                base::node::MAIN_SAMPLED_COLOR_ATTACHMENT,
+                WindowTextureNode::OUT_TEXTURE,
+                node::CANVAS,
+                COLOR_ATTACHMENT_SLOT,
+            )
+            .unwrap();
+        render_graph
+            .add_slot_edge(
+                base::node::PRIMARY_SWAP_CHAIN,
+                WindowSwapChainNode::OUT_TEXTURE,
+                node::CANVAS,
+                COLOR_RESOLVE_TARGET_SLOT,
+            )
+            .unwrap();
+    } else {
+        render_graph
+            .add_slot_edge(
+                base::node::PRIMARY_SWAP_CHAIN,
+                WindowSwapChainNode::OUT_TEXTURE,
+                node::CANVAS,
+                COLOR_ATTACHMENT_SLOT,
+            )
+            .unwrap();
+    }
 
     render_graph
         .add_slot_edge(
@@ -216,6 +732,50 @@ pub(crate) fn setup_canvas_node(world: &mut World) {
         .unwrap();
 }
 
+/// Lets canvases draw through the camera with the given name, adding it to the
+/// active cameras and to the render graph.
+///
+/// Cameras that are already active, like the ones managed by Bevy, keep their
+/// node, which is only ordered before the canvas.
+pub(crate) fn setup_canvas_camera(world: &mut World, camera: &str) {
+    let world = world.cell();
+    let mut active_cameras = world.get_resource_mut::<ActiveCameras>().unwrap();
+    let mut render_graph = world.get_resource_mut::<RenderGraph>().unwrap();
+
+    let camera_node = camera_node(camera);
+    if active_cameras.get(camera).is_none() {
+        active_cameras.add(camera);
+        render_graph.add_system_node(camera_node.clone(), CameraNode::new(camera.to_owned()));
+    }
+    add_camera_edges(&mut render_graph, &camera_node);
+}
+
+/// Returns the name of the render graph node that computes the
+/// view-projection matrix of the camera with the given name.
+fn camera_node(camera: &str) -> String {
+    match camera {
+        base::camera::CAMERA_2D => base::node::CAMERA_2D.to_owned(),
+        base::camera::CAMERA_3D => base::node::CAMERA_3D.to_owned(),
+        CAMERA_UI => CAMERA_UI_NODE.to_owned(),
+        _ => format!("{}{}", CANVAS_CAMERA_NODE_PREFIX, camera),
+    }
+}
+
+/// Makes the canvas nodes run after the camera node with the given name, if it
+/// exists.
+fn add_camera_edges(render_graph: &mut RenderGraph, camera_node: &str) {
+    if render_graph.get_node_id(camera_node).is_err() {
+        return;
+    }
+
+    for canvas_node in [node::CANVAS, node::OFFSCREEN_CANVAS].iter() {
+        let result = render_graph.add_node_edge(camera_node, *canvas_node);
+        if !matches!(result, Err(RenderGraphError::EdgeAlreadyExists(_))) {
+            result.unwrap();
+        }
+    }
+}
+
 fn pass_descriptor(input: &ResourceSlots, sample_count: u32) -> PassDescriptor {
     let color_texture = input
         .get(COLOR_ATTACHMENT_SLOT)
@@ -229,10 +789,19 @@ fn pass_descriptor(input: &ResourceSlots, sample_count: u32) -> PassDescriptor {
         .get_texture()
         .unwrap();
 
+    let resolve_target = if sample_count > 1 {
+        input
+            .get(COLOR_RESOLVE_TARGET_SLOT)
+            .and_then(|resource| resource.get_texture())
+            .map(TextureAttachment::Id)
+    } else {
+        None
+    };
+
     PassDescriptor {
         color_attachments: vec![RenderPassColorAttachment {
             attachment: TextureAttachment::Id(color_texture),
-            resolve_target: None,
+            resolve_target,
             ops: Operations {
                 load: LoadOp::Load,
                 store: true,
@@ -251,29 +820,69 @@ fn pass_descriptor(input: &ResourceSlots, sample_count: u32) -> PassDescriptor {
 }
 
 fn pipeline_descriptor(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
-    // BUG: Setting a multisample state with more than 1 sample causes a
-    // validation error even if MSAA is set to many samples.
-
-    // TODO: Remove this panic after implementing WebGL support!
-    if cfg!(target_arch = "wasm32") {
-        panic!("Currently bevy_canvas does not support WebGL shaders. Feel free to submit a PR :)");
-    }
-
     PipelineDescriptor {
         name: Some("CanvasPipeline".into()),
         layout: Some(PipelineLayout {
-            bind_groups: vec![BindGroupDescriptor::new(
-                0,
-                vec![BindingDescriptor {
-                    name: "Camera".into(),
-                    index: 0,
-                    bind_type: BindType::Uniform {
-                        has_dynamic_offset: false,
-                        property: UniformProperty::Struct(vec![UniformProperty::Mat4]),
-                    },
-                    shader_stage: BindingShaderStage::VERTEX,
-                }],
-            )],
+            bind_groups: vec![
+                BindGroupDescriptor::new(
+                    0,
+                    vec![BindingDescriptor {
+                        name: "Camera".into(),
+                        index: 0,
+                        bind_type: BindType::Uniform {
+                            has_dynamic_offset: false,
+                            property: UniformProperty::Struct(vec![UniformProperty::Mat4]),
+                        },
+                        shader_stage: BindingShaderStage::VERTEX,
+                    }],
+                ),
+                BindGroupDescriptor::new(
+                    1,
+                    vec![
+                        BindingDescriptor {
+                            name: "CanvasTexture".into(),
+                            index: 0,
+                            bind_type: BindType::Texture {
+                                multisampled: false,
+                                view_dimension: TextureViewDimension::D2,
+                                sample_type: TextureSampleType::Float { filterable: true },
+                            },
+                            shader_stage: BindingShaderStage::FRAGMENT,
+                        },
+                        BindingDescriptor {
+                            name: "CanvasTexture_sampler".into(),
+                            index: 1,
+                            bind_type: BindType::Sampler {
+                                comparison: false,
+                                filtering: true,
+                            },
+                            shader_stage: BindingShaderStage::FRAGMENT,
+                        },
+                    ],
+                ),
+                BindGroupDescriptor::new(
+                    2,
+                    vec![BindingDescriptor {
+                        name: "CanvasGradient".into(),
+                        index: 0,
+                        bind_type: BindType::Uniform {
+                            has_dynamic_offset: false,
+                            property: UniformProperty::Struct(vec![
+                                UniformProperty::Vec4,
+                                UniformProperty::Array(
+                                    Box::new(UniformProperty::Vec4),
+                                    MAX_GRADIENT_STOPS,
+                                ),
+                                UniformProperty::Array(
+                                    Box::new(UniformProperty::Vec4),
+                                    MAX_GRADIENT_STOPS,
+                                ),
+                            ]),
+                        },
+                        shader_stage: BindingShaderStage::FRAGMENT,
+                    }],
+                ),
+            ],
             vertex_buffer_descriptors: vec![VertexBufferLayout {
                 name: "CanvasVertexBuffer".into(),
                 stride: size_of::<Vertex>() as u64,
@@ -281,16 +890,22 @@ fn pipeline_descriptor(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
                 attributes: vec![
                     VertexAttribute {
                         name: "position".into(),
-                        offset: 0,
+                        offset: Vertex::POSITION_OFFSET,
                         format: VertexFormat::Float32x3,
                         shader_location: 0,
                     },
                     VertexAttribute {
                         name: "color".into(),
-                        offset: VertexFormat::Float32x4.get_size(),
+                        offset: Vertex::COLOR_OFFSET,
                         format: VertexFormat::Float32x4,
                         shader_location: 1,
                     },
+                    VertexAttribute {
+                        name: "uv".into(),
+                        offset: Vertex::UV_OFFSET,
+                        format: VertexFormat::Float32x2,
+                        shader_location: 2,
+                    },
                 ],
             }],
         }),
@@ -349,3 +964,24 @@ fn pipeline_descriptor(shaders: &mut Assets<Shader>) -> PipelineDescriptor {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use glsl::{parser::Parse, syntax::ShaderStage};
+
+    /// Parses a GLSL ES 3.00 shader, used by WebGL2 builds.
+    fn parse_es_shader(source: &str) -> ShaderStage {
+        assert!(source.starts_with("#version 300 es"));
+        ShaderStage::parse(source).unwrap()
+    }
+
+    #[test]
+    fn es_vertex_shader_parses() {
+        parse_es_shader(include_str!("shader.es.vert"));
+    }
+
+    #[test]
+    fn es_fragment_shader_parses() {
+        parse_es_shader(include_str!("shader.es.frag"));
+    }
+}
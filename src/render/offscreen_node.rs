@@ -0,0 +1,203 @@
+use bevy::{
+    asset::Handle,
+    ecs::{
+        entity::Entity,
+        world::{Mut, World},
+    },
+    math::{Mat4, Vec2},
+    render::{
+        color::Color,
+        pass::{
+            LoadOp, Operations, PassDescriptor, RenderPassColorAttachment,
+            RenderPassDepthStencilAttachment, TextureAttachment,
+        },
+        render_graph::{Node, ResourceSlots},
+        renderer::{RenderContext, RenderResourceContext, RenderResourceId, SamplerId, TextureId},
+        texture::{
+            Extent3d, SamplerDescriptor, Texture, TextureDescriptor, TextureFormat, TextureUsage,
+            SAMPLER_ASSET_INDEX, TEXTURE_ASSET_INDEX,
+        },
+    },
+    utils::HashMap,
+};
+
+use super::{
+    buffer::GeometryBuffers,
+    types::{CanvasGeometry, Viewport},
+    CanvasRenderer,
+};
+use crate::{Canvas, CanvasTexture};
+
+/// Draws every [`Canvas`] component into the texture described by the
+/// [`CanvasTexture`] of its entity.
+#[derive(Debug, Default)]
+pub struct OffscreenCanvasNode {
+    targets: HashMap<Entity, OffscreenTarget>,
+    renderer: CanvasRenderer,
+}
+
+#[derive(Debug)]
+struct OffscreenTarget {
+    texture: Handle<Texture>,
+    size: Extent3d,
+    clear_color: Color,
+    /// The projection of world space on the texture, if the cameras aren't
+    /// used.
+    world_projection: Option<Mat4>,
+    color_attachment: TextureId,
+    depth_attachment: TextureId,
+    sampler: SamplerId,
+    geometry: CanvasGeometry,
+    buffers: GeometryBuffers,
+    /// Whether the entity still had its canvas and target in the last call to
+    /// `prepare`.
+    alive: bool,
+}
+
+impl OffscreenTarget {
+    fn new(
+        render_resource_context: &dyn RenderResourceContext,
+        texture: Handle<Texture>,
+        size: Extent3d,
+    ) -> Self {
+        let color_attachment = render_resource_context.create_texture(TextureDescriptor {
+            size,
+            format: TextureFormat::default(),
+            usage: TextureUsage::SAMPLED | TextureUsage::RENDER_ATTACHMENT,
+            ..Default::default()
+        });
+        let depth_attachment = render_resource_context.create_texture(TextureDescriptor {
+            size,
+            format: TextureFormat::Depth32Float,
+            usage: TextureUsage::RENDER_ATTACHMENT,
+            ..Default::default()
+        });
+        let sampler = render_resource_context.create_sampler(&SamplerDescriptor::default());
+
+        // Lets the rest of the engine use the color attachment through the
+        // texture handle.
+        render_resource_context.set_asset_resource(
+            &texture,
+            RenderResourceId::Texture(color_attachment),
+            TEXTURE_ASSET_INDEX,
+        );
+        render_resource_context.set_asset_resource(
+            &texture,
+            RenderResourceId::Sampler(sampler),
+            SAMPLER_ASSET_INDEX,
+        );
+
+        Self {
+            texture,
+            size,
+            clear_color: Color::NONE,
+            world_projection: None,
+            color_attachment,
+            depth_attachment,
+            sampler,
+            geometry: CanvasGeometry::new(),
+            buffers: GeometryBuffers::default(),
+            alive: true,
+        }
+    }
+
+    fn remove(&self, render_resource_context: &dyn RenderResourceContext) {
+        render_resource_context.remove_asset_resource(&self.texture, TEXTURE_ASSET_INDEX);
+        render_resource_context.remove_asset_resource(&self.texture, SAMPLER_ASSET_INDEX);
+        render_resource_context.remove_texture(self.color_attachment);
+        render_resource_context.remove_texture(self.depth_attachment);
+        render_resource_context.remove_sampler(self.sampler);
+        self.buffers.remove(render_resource_context);
+    }
+
+    fn pass_descriptor(&self) -> PassDescriptor {
+        PassDescriptor {
+            color_attachments: vec![RenderPassColorAttachment {
+                attachment: TextureAttachment::Id(self.color_attachment),
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(self.clear_color),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                attachment: TextureAttachment::Id(self.depth_attachment),
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: None,
+            }),
+            sample_count: 1,
+        }
+    }
+}
+
+impl Node for OffscreenCanvasNode {
+    fn prepare(&mut self, world: &mut World) {
+        let targets = &mut self.targets;
+        world.resource_scope(
+            |world, render_resource_context: Mut<Box<dyn RenderResourceContext>>| {
+                let render_resource_context = &**render_resource_context;
+                for target in targets.values_mut() {
+                    target.alive = false;
+                }
+
+                let mut query = world.query::<(Entity, &mut Canvas, &CanvasTexture)>();
+                for (entity, mut canvas, canvas_texture) in query.iter_mut(world) {
+                    let size =
+                        Extent3d::new(canvas_texture.width.max(1), canvas_texture.height.max(1), 1);
+                    let outdated = targets.get(&entity).map_or(true, |target| {
+                        target.texture != canvas_texture.texture || target.size != size
+                    });
+                    if outdated {
+                        if let Some(target) = targets.remove(&entity) {
+                            target.remove(render_resource_context);
+                        }
+                        let texture = canvas_texture.texture.clone_weak();
+                        let target = OffscreenTarget::new(render_resource_context, texture, size);
+                        targets.insert(entity, target);
+                    }
+
+                    let target = targets.get_mut(&entity).unwrap();
+                    target.alive = true;
+                    target.clear_color = canvas_texture.clear_color;
+                    target.world_projection = canvas_texture.world_projection();
+                    canvas.flush(&mut target.geometry);
+                }
+
+                // Releases the textures of the canvases that were despawned or
+                // lost their target.
+                targets.retain(|_, target| {
+                    if !target.alive {
+                        target.remove(render_resource_context);
+                    }
+                    target.alive
+                });
+            },
+        );
+    }
+
+    fn update(
+        &mut self,
+        world: &World,
+        render_context: &mut dyn RenderContext,
+        _input: &ResourceSlots,
+        _output: &mut ResourceSlots,
+    ) {
+        for target in self.targets.values_mut() {
+            self.renderer.draw(
+                world,
+                render_context,
+                &target.pass_descriptor(),
+                &target.geometry,
+                &mut target.buffers,
+                Viewport {
+                    physical_size: Vec2::new(target.size.width as f32, target.size.height as f32),
+                    scale_factor: 1.0,
+                    world_projection: target.world_projection,
+                },
+            );
+        }
+    }
+}
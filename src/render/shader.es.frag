@@ -0,0 +1,59 @@
+#version 300 es
+
+precision highp float;
+
+in vec4 v_color;
+in vec2 v_uv;
+
+out vec4 o_Target;
+
+uniform sampler2D CanvasTexture;  // set = 1, binding = 0
+
+layout(std140) uniform CanvasGradient {  // set = 2, binding = 0
+    // The kind of gradient, 0 if there's none, and the number of stops.
+    vec4 Gradient_params;
+    vec4 Gradient_offsets[16];
+    vec4 Gradient_colors[16];
+};
+
+// Returns the offset along the gradient of the fragment, from its gradient
+// coordinates.
+float gradient_offset(int kind) {
+    if (kind == 1) {
+        return v_uv.x;
+    } else if (kind == 2) {
+        return length(v_uv);
+    } else {
+        return fract(atan(v_uv.y, v_uv.x) / 6.28318530718);
+    }
+}
+
+// Returns the color at the offset `t` of the gradient, between the stops
+// around it.
+vec4 gradient_color(float t) {
+    int count = int(Gradient_params.y);
+    if (count == 0) {
+        return vec4(0.0);
+    }
+    if (t <= Gradient_offsets[0].x) {
+        return Gradient_colors[0];
+    }
+    for (int i = 1; i < count; i++) {
+        float start = Gradient_offsets[i - 1].x;
+        float end = Gradient_offsets[i].x;
+        if (t <= end) {
+            float factor = end > start ? (t - start) / (end - start) : 1.0;
+            return mix(Gradient_colors[i - 1], Gradient_colors[i], factor);
+        }
+    }
+    return Gradient_colors[count - 1];
+}
+
+void main() {
+    int kind = int(Gradient_params.x);
+    vec4 paint = texture(CanvasTexture, v_uv);
+    if (kind != 0) {
+        paint = gradient_color(gradient_offset(kind));
+    }
+    o_Target = v_color * paint;
+}
\ No newline at end of file
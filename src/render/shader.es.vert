@@ -0,0 +1,20 @@
+#version 300 es
+
+precision highp float;
+
+in vec3 vertex_position;
+in vec4 vertex_color;
+in vec2 vertex_uv;
+
+out vec4 v_color;
+out vec2 v_uv;
+
+layout(std140) uniform CameraViewProj {  // set = 0, binding = 0
+    mat4 ViewProj;
+};
+
+void main() {
+    gl_Position = ViewProj * vec4(vertex_position, 1.0);
+    v_color = vertex_color;
+    v_uv = vertex_uv;
+}
\ No newline at end of file
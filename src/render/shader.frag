@@ -1,9 +1,58 @@
 #version 450
 
 layout(location = 0) in vec4 v_color;
+layout(location = 1) in vec2 v_uv;
 
 layout(location = 0) out vec4 o_Target;
 
+layout(set = 1, binding = 0) uniform texture2D CanvasTexture;
+layout(set = 1, binding = 1) uniform sampler CanvasTexture_sampler;
+
+layout(set = 2, binding = 0) uniform CanvasGradient {
+    // The kind of gradient, 0 if there's none, and the number of stops.
+    vec4 Gradient_params;
+    vec4 Gradient_offsets[16];
+    vec4 Gradient_colors[16];
+};
+
+// Returns the offset along the gradient of the fragment, from its gradient
+// coordinates.
+float gradient_offset(int kind) {
+    if (kind == 1) {
+        return v_uv.x;
+    } else if (kind == 2) {
+        return length(v_uv);
+    } else {
+        return fract(atan(v_uv.y, v_uv.x) / 6.28318530718);
+    }
+}
+
+// Returns the color at the offset `t` of the gradient, between the stops
+// around it.
+vec4 gradient_color(float t) {
+    int count = int(Gradient_params.y);
+    if (count == 0) {
+        return vec4(0.0);
+    }
+    if (t <= Gradient_offsets[0].x) {
+        return Gradient_colors[0];
+    }
+    for (int i = 1; i < count; i++) {
+        float start = Gradient_offsets[i - 1].x;
+        float end = Gradient_offsets[i].x;
+        if (t <= end) {
+            float factor = end > start ? (t - start) / (end - start) : 1.0;
+            return mix(Gradient_colors[i - 1], Gradient_colors[i], factor);
+        }
+    }
+    return Gradient_colors[count - 1];
+}
+
 void main() {
-    o_Target = v_color;
+    int kind = int(Gradient_params.x);
+    vec4 paint = texture(sampler2D(CanvasTexture, CanvasTexture_sampler), v_uv);
+    if (kind != 0) {
+        paint = gradient_color(gradient_offset(kind));
+    }
+    o_Target = v_color * paint;
 }
\ No newline at end of file
@@ -1,15 +1,18 @@
 #version 450
 
-layout(location = 0) in vec2 vertex_position_2d;
+layout(location = 0) in vec3 vertex_position;
 layout(location = 1) in vec4 vertex_color;
+layout(location = 2) in vec2 vertex_uv;
 
 layout(location = 0) out vec4 v_color;
+layout(location = 1) out vec2 v_uv;
 
 layout(set = 0, binding = 0) uniform CameraViewProj {
     mat4 ViewProj;
 };
 
 void main() {
-    gl_Position = ViewProj * vec4(vertex_position_2d, 0.0, 1.0);
+    gl_Position = ViewProj * vec4(vertex_position, 1.0);
     v_color = vertex_color;
+    v_uv = vertex_uv;
 }
\ No newline at end of file
@@ -1,49 +1,208 @@
+use std::{borrow::Cow, mem::size_of, ops::Range};
+
 use bevy::{
+    asset::Handle,
     core::{Pod, Zeroable},
-    math::{Vec2, Vec4},
-    render::color::Color,
+    math::{Mat4, Vec2},
+    render::{render_graph::base, texture::Texture},
 };
 use tess::{
     math::{point, vector, Point, Vector},
     FillVertex, FillVertexConstructor, StrokeVertex, StrokeVertexConstructor,
 };
 
-// TODO: Should a z-component be added?
+use crate::{CoordinateSpace, Paint, Transform2d};
+
+/// A vertex as laid out in the vertex buffer. The fields are arrays of `f32`,
+/// so they follow each other without padding.
 #[derive(Debug, Default, Clone, Copy)]
 #[repr(C)]
 pub(crate) struct Vertex {
-    pub pos: Vec2,
-    pub color: Vec4,
+    pub pos: [f32; 3],
+    /// The linear RGBA color.
+    pub color: [f32; 4],
+    pub uv: [f32; 2],
+}
+
+impl Vertex {
+    pub const COLOR_OFFSET: u64 = Self::POSITION_OFFSET + size_of::<[f32; 3]>() as u64;
+    /// The offsets of the fields from the start of the vertex, in bytes.
+    pub const POSITION_OFFSET: u64 = 0;
+    pub const UV_OFFSET: u64 = Self::COLOR_OFFSET + size_of::<[f32; 4]>() as u64;
 }
 
 unsafe impl Zeroable for Vertex {}
 unsafe impl Pod for Vertex {}
 
+/// The maximum number of color stops of a gradient evaluated by the fragment
+/// shader.
+pub(crate) const MAX_GRADIENT_STOPS: usize = 16;
+
+/// A gradient as laid out in the uniform block read by the fragment shader,
+/// which evaluates it at the gradient coordinates stored in the `uv` of the
+/// vertices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub(crate) struct Gradient {
+    /// The kind of gradient, `0.0` for none, `1.0` for linear, `2.0` for
+    /// radial and `3.0` for conic, followed by the number of stops.
+    pub params: [f32; 4],
+    /// The offsets of the stops, in the first component of each element.
+    pub offsets: [[f32; 4]; MAX_GRADIENT_STOPS],
+    /// The linear RGBA colors of the stops.
+    pub colors: [[f32; 4]; MAX_GRADIENT_STOPS],
+}
+
+unsafe impl Zeroable for Gradient {}
+unsafe impl Pod for Gradient {}
+
 type IndexType = u32;
 pub(crate) type BufferPair = tess::VertexBuffers<Vertex, IndexType>;
 
-pub(crate) struct VertexConstructor {
-    pub color: Color,
+/// Appends the geometry stored in `src` at the end of `dst`.
+pub(crate) fn append_buffers(dst: &mut BufferPair, src: &BufferPair) {
+    let offset = dst.vertices.len() as IndexType;
+    dst.vertices.extend_from_slice(&src.vertices);
+    dst.indices.extend(src.indices.iter().map(|i| i + offset));
 }
 
-impl FillVertexConstructor<Vertex> for VertexConstructor {
-    fn new_vertex(&mut self, vertex: FillVertex) -> Vertex {
-        Vertex {
-            pos: Vec2::new(vertex.position().x, vertex.position().y),
-            color: self.color.into(),
+/// The part of the drawing state of a canvas that can't be baked into the
+/// vertices, and must be set in the render pass instead.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RenderState {
+    /// The name of the camera used to show the shapes.
+    pub camera: Cow<'static, str>,
+    pub space: CoordinateSpace,
+    /// The texture that the vertex colors are multiplied by. A white texture
+    /// is used if it's `None`.
+    pub texture: Option<Handle<Texture>>,
+    /// The gradient that the vertex colors are multiplied by, if any.
+    pub gradient: Option<Gradient>,
+    /// The rectangles that the shapes are clipped to. Each shape is only
+    /// drawn inside all of them.
+    pub clips: Vec<ClipRect>,
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        Self {
+            camera: Cow::Borrowed(base::camera::CAMERA_2D),
+            space: CoordinateSpace::World,
+            texture: None,
+            gradient: None,
+            clips: Vec::new(),
         }
     }
 }
 
-impl StrokeVertexConstructor<Vertex> for VertexConstructor {
-    fn new_vertex(&mut self, vertex: StrokeVertex) -> Vertex {
+/// An axis-aligned rectangle outside of which shapes are not drawn, defined in
+/// the coordinate system of a camera or of a screen space.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ClipRect {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub camera: Cow<'static, str>,
+    pub space: CoordinateSpace,
+}
+
+/// The size of the render target of a canvas.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Viewport {
+    pub physical_size: Vec2,
+    pub scale_factor: f32,
+    /// The projection of world space on the render target, used instead of
+    /// the cameras if any.
+    pub world_projection: Option<Mat4>,
+}
+
+/// A range of indices drawn with the same render state.
+#[derive(Debug, Clone)]
+pub(crate) struct DrawCall {
+    pub indices: Range<u32>,
+    pub render_state: RenderState,
+}
+
+/// The geometry of a canvas, ready to be uploaded to the GPU.
+#[derive(Debug)]
+pub(crate) struct CanvasGeometry {
+    pub buffers: BufferPair,
+    pub draw_calls: Vec<DrawCall>,
+}
+
+impl CanvasGeometry {
+    pub fn new() -> Self {
+        Self {
+            buffers: BufferPair::new(),
+            draw_calls: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.buffers.vertices.clear();
+        self.buffers.indices.clear();
+        self.draw_calls.clear();
+    }
+
+    /// Appends the geometry stored in `src`, to be drawn with `render_state`.
+    ///
+    /// The geometry is merged into the last draw call if it has the same
+    /// render state. Nothing is appended if `src` has no triangles.
+    pub fn append(&mut self, src: &BufferPair, render_state: &RenderState) {
+        if src.indices.is_empty() {
+            return;
+        }
+
+        let start = self.buffers.indices.len() as u32;
+        append_buffers(&mut self.buffers, src);
+        let end = self.buffers.indices.len() as u32;
+
+        match self.draw_calls.last_mut() {
+            Some(last) if last.render_state == *render_state => last.indices.end = end,
+            _ => self.draw_calls.push(DrawCall {
+                indices: start..end,
+                render_state: render_state.clone(),
+            }),
+        }
+    }
+}
+
+pub(crate) struct VertexConstructor<'a> {
+    pub paint: &'a Paint,
+    pub z: f32,
+    /// Maps the tessellated positions back to the coordinate system of the
+    /// shape, where the paint is defined.
+    pub to_local: Transform2d,
+    /// Maps the coordinate system of the shape to texture coordinates.
+    pub to_uv: Transform2d,
+}
+
+impl VertexConstructor<'_> {
+    fn vertex_at(&self, position: Point, attributes: &[f32]) -> Vertex {
+        let position = Vec2::new(position.x, position.y);
+        let local = self.to_local.transform_point(position);
+
         Vertex {
-            pos: Vec2::new(vertex.position().x, vertex.position().y),
-            color: self.color.into(),
+            pos: position.extend(self.z).into(),
+            color: self.paint.color_at(local, attributes).into(),
+            uv: self.to_uv.transform_point(local).into(),
         }
     }
 }
 
+impl FillVertexConstructor<Vertex> for VertexConstructor<'_> {
+    fn new_vertex(&mut self, mut vertex: FillVertex) -> Vertex {
+        let position = vertex.position();
+        self.vertex_at(position, vertex.interpolated_attributes())
+    }
+}
+
+impl StrokeVertexConstructor<Vertex> for VertexConstructor<'_> {
+    fn new_vertex(&mut self, mut vertex: StrokeVertex) -> Vertex {
+        let position = vertex.position();
+        self.vertex_at(position, vertex.interpolated_attributes())
+    }
+}
+
 pub(crate) trait Conversion {
     fn to_point(&self) -> Point;
     fn to_vector(&self) -> Vector;
@@ -58,3 +217,21 @@ impl Conversion for Vec2 {
         vector(self.x, self.y)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_offsets_match_its_layout() {
+        let vertex = Vertex::default();
+        let offset = |field: *const f32| field as usize - &vertex as *const Vertex as usize;
+        assert_eq!(offset(vertex.pos.as_ptr()) as u64, Vertex::POSITION_OFFSET);
+        assert_eq!(offset(vertex.color.as_ptr()) as u64, Vertex::COLOR_OFFSET);
+        assert_eq!(offset(vertex.uv.as_ptr()) as u64, Vertex::UV_OFFSET);
+        assert_eq!(
+            size_of::<Vertex>() as u64,
+            Vertex::UV_OFFSET + size_of::<[f32; 2]>() as u64
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::math::{Mat4, Vec2};
+
+/// The coordinate system of the shapes drawn on a [`Canvas`](crate::Canvas).
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{common_shapes::Circle, Canvas, CoordinateSpace, DrawMode, ScreenOrigin};
+///
+/// fn crosshair_system(mut canvas: ResMut<Canvas>) {
+///     let crosshair = Circle {
+///         center: Vec2::ZERO,
+///         radius: 8.0,
+///     };
+///
+///     canvas.save();
+///     canvas.set_space(CoordinateSpace::Screen {
+///         origin: ScreenOrigin::Center,
+///         dpi_aware: true,
+///     });
+///     canvas.draw(&crosshair, DrawMode::stroke_1px(), Color::WHITE);
+///     canvas.restore();
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoordinateSpace {
+    /// World units, as seen from the camera of the canvas.
+    World,
+    /// Pixels from a point of the render target, regardless of the camera.
+    ///
+    /// The x-axis points right and the y-axis points up, like in world space.
+    /// The z-coordinate, from `-1000.0` to `1000.0`, sorts the shapes drawn in
+    /// screen space among themselves. They share the depth buffer with the
+    /// world, and are only shown in front of what the 2D camera shows at
+    /// z < 500: world content closer to the camera can cover them.
+    Screen {
+        /// The point of the render target at the origin of the coordinate
+        /// system.
+        origin: ScreenOrigin,
+        /// Whether the coordinates are in logical pixels, that follow the
+        /// scale factor of the window, instead of physical pixels.
+        dpi_aware: bool,
+    },
+}
+
+impl CoordinateSpace {
+    /// Returns the projection matrix of a screen space for a render target
+    /// with the given size in physical pixels, or `None` in world space.
+    pub(crate) fn screen_projection(&self, physical_size: Vec2, scale_factor: f32) -> Option<Mat4> {
+        match *self {
+            CoordinateSpace::World => None,
+            CoordinateSpace::Screen { origin, dpi_aware } => {
+                let size = if dpi_aware {
+                    physical_size / scale_factor
+                } else {
+                    physical_size
+                };
+                let bottom_left = -size * origin.fraction();
+                let top_right = bottom_left + size;
+
+                // Maps the z-coordinates to the front half of the depth range,
+                // where they cover what the 2D camera shows at z < 500.
+                Some(Mat4::orthographic_rh(
+                    bottom_left.x,
+                    top_right.x,
+                    bottom_left.y,
+                    top_right.y,
+                    -1000.0,
+                    3000.0,
+                ))
+            }
+        }
+    }
+}
+
+impl Default for CoordinateSpace {
+    fn default() -> Self {
+        Self::World
+    }
+}
+
+/// A point of the render target used as the origin of a screen space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScreenOrigin {
+    Center,
+    BottomLeft,
+    BottomRight,
+    TopRight,
+    TopLeft,
+}
+
+impl ScreenOrigin {
+    /// The position of the origin, as a fraction of the render target size
+    /// starting from the bottom-left corner.
+    fn fraction(&self) -> Vec2 {
+        match self {
+            ScreenOrigin::Center => Vec2::new(0.5, 0.5),
+            ScreenOrigin::BottomLeft => Vec2::new(0.0, 0.0),
+            ScreenOrigin::BottomRight => Vec2::new(1.0, 0.0),
+            ScreenOrigin::TopRight => Vec2::new(1.0, 1.0),
+            ScreenOrigin::TopLeft => Vec2::new(0.0, 1.0),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// A Bevy `Resource` with statistics about the shapes drawn by the
+/// [`Canvas`](crate::Canvas) resource, updated every time they are rendered.
+///
+/// Since the canvas is rendered after the systems run, the statistics read by a
+/// system describe the previous frame. Canvases drawing into a
+/// [`CanvasTexture`](crate::CanvasTexture) are not counted.
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::CanvasStats;
+///
+/// fn profiling_system(stats: Res<CanvasStats>) {
+///     info!(
+///         "{} vertices, {} draw calls, tessellated in {:?}",
+///         stats.vertices, stats.draw_calls, stats.tessellation_time
+///     );
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CanvasStats {
+    /// The number of vertices uploaded to the GPU, including the ones of the
+    /// passive layer.
+    pub vertices: usize,
+    pub indices: usize,
+    /// The number of draw calls, one for each run of shapes sharing the same
+    /// camera, coordinate space, texture and clipping region.
+    pub draw_calls: usize,
+    /// The time spent tessellating the shapes drawn during the frame.
+    pub tessellation_time: Duration,
+}
@@ -0,0 +1,230 @@
+use tess::{
+    math::{vector, Point, Vector},
+    path::{path::Builder, traits::PathBuilder, Path, PathEvent},
+    StrokeOptions,
+};
+
+/// Determines the width of a variable stroke along a sub-path.
+#[derive(Debug, Clone, Copy)]
+pub enum StrokeWidth {
+    /// The width changes linearly from the start to the end of each sub-path.
+    Linear { start: f32, end: f32 },
+    /// The width is computed by a function of the distance along each
+    /// sub-path, normalized from `0.0` at its start to `1.0` at its end.
+    Function(fn(f32) -> f32),
+}
+
+impl StrokeWidth {
+    fn at(&self, t: f32) -> f32 {
+        match *self {
+            StrokeWidth::Linear { start, end } => start + (end - start) * t,
+            StrokeWidth::Function(f) => f(t),
+        }
+    }
+}
+
+/// Parameters of a stroke whose width varies along the path.
+///
+/// The shape is flattened into line segments, which are then widened and
+/// filled. Sub-paths end with butt caps and their corners are joined with
+/// clamped miter joins.
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{common_shapes::Line, Canvas, DrawMode, StrokeWidth, VariableStrokeOptions};
+///
+/// fn trail_system(mut canvas: ResMut<Canvas>) {
+///     let trail = Line(Vec2::ZERO, Vec2::new(300.0, 0.0));
+///     let options = VariableStrokeOptions::new(StrokeWidth::Linear {
+///         start: 0.0,
+///         end: 20.0,
+///     });
+///
+///     canvas.draw(&trail, DrawMode::VariableStroke(options), Color::CYAN);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct VariableStrokeOptions {
+    pub width: StrokeWidth,
+    /// The maximum distance between the curves of the shape and the line
+    /// segments they are approximated with.
+    pub tolerance: f32,
+    /// The maximum ratio between the length of a miter join and the width of
+    /// the stroke.
+    pub miter_limit: f32,
+}
+
+impl VariableStrokeOptions {
+    pub fn new(width: StrokeWidth) -> Self {
+        Self {
+            width,
+            tolerance: StrokeOptions::DEFAULT_TOLERANCE,
+            miter_limit: StrokeOptions::DEFAULT_MITER_LIMIT,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+}
+
+/// Returns the outline of a variable stroke of `path`, to be filled with the
+/// nonzero fill rule.
+pub(crate) fn variable_stroke_outline(path: &Path, options: &VariableStrokeOptions) -> Path {
+    let mut b = Builder::new();
+    let mut points = Vec::new();
+    for event in path.iter().flattened(options.tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                points.clear();
+                points.push(at);
+            }
+            PathEvent::Line { to, .. } => {
+                if points.last() != Some(&to) {
+                    points.push(to);
+                }
+            }
+            PathEvent::End { first, close, .. } => {
+                if close && points.len() > 2 && points.last() != Some(&first) {
+                    points.push(first);
+                }
+                add_sub_path(&mut b, &points, close, options);
+            }
+            _ => {}
+        }
+    }
+
+    b.build()
+}
+
+/// Adds the outline of the stroke of a flattened sub-path.
+///
+/// Open sub-paths are outlined by a single polygon, going forward along the
+/// left side and back along the right side. Closed sub-paths are outlined by
+/// one polygon per side, with opposite orientations.
+fn add_sub_path(b: &mut Builder, points: &[Point], closed: bool, options: &VariableStrokeOptions) {
+    if points.len() < 2 {
+        return;
+    }
+    // A closed sub-path needs at least three distinct points to have
+    // neighbours on both sides of each point, so smaller ones are outlined
+    // like open ones.
+    let closed = closed && points.len() > 3;
+
+    let mut lengths = Vec::with_capacity(points.len());
+    let mut length = 0.0;
+    lengths.push(0.0);
+    for pair in points.windows(2) {
+        length += (pair[1] - pair[0]).length();
+        lengths.push(length);
+    }
+    if length <= 0.0 {
+        return;
+    }
+
+    let mut left = Vec::with_capacity(points.len());
+    let mut right = Vec::with_capacity(points.len());
+    let last = points.len() - 1;
+    for (i, &p) in points.iter().enumerate() {
+        // In closed sub-paths the first and the last point are the same, so
+        // they are joined like the others.
+        let previous = match i {
+            0 if closed => Some(points[last - 1]),
+            0 => None,
+            _ => Some(points[i - 1]),
+        };
+        let next = match i {
+            i if i == last && closed => Some(points[1]),
+            i if i == last => None,
+            _ => Some(points[i + 1]),
+        };
+        let offset = join_offset(p, previous, next, options.miter_limit);
+
+        let half_width = options.width.at(lengths[i] / length).max(0.0) / 2.0;
+        left.push(p + offset * half_width);
+        right.push(p - offset * half_width);
+    }
+
+    if closed {
+        add_polygon(b, left.iter());
+        add_polygon(b, right.iter().rev());
+    } else {
+        add_polygon(b, left.iter().chain(right.iter().rev()));
+    }
+}
+
+/// Returns the vector that moves a point of a polyline to the left side of a
+/// stroke of unit half-width.
+fn join_offset(p: Point, previous: Option<Point>, next: Option<Point>, miter_limit: f32) -> Vector {
+    let normal = |from: Point, to: Point| {
+        let d = (to - from).normalize();
+        vector(-d.y, d.x)
+    };
+
+    match (previous, next) {
+        (Some(previous), Some(next)) => {
+            let n0 = normal(previous, p);
+            let n1 = normal(p, next);
+            let sum = n0 + n1;
+            if sum.square_length() < 1e-6 {
+                return n0;
+            }
+            let miter = sum.normalize();
+            // The miter gets longer as the corner gets sharper.
+            let scale = (1.0 / miter.dot(n0)).min(miter_limit);
+            miter * scale
+        }
+        (Some(previous), None) => normal(previous, p),
+        (None, Some(next)) => normal(p, next),
+        (None, None) => vector(0.0, 0.0),
+    }
+}
+
+fn add_polygon<'a>(b: &mut Builder, mut points: impl Iterator<Item = &'a Point>) {
+    if let Some(&first) = points.next() {
+        b.begin(first);
+        for &p in points {
+            b.line_to(p);
+        }
+        b.end(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tess::math::point;
+
+    use super::*;
+
+    #[test]
+    fn closed_sub_path_with_two_points_has_finite_outline() {
+        let mut b = Path::builder();
+        b.begin(point(0.0, 0.0));
+        b.line_to(point(10.0, 0.0));
+        b.end(true);
+        let path = b.build();
+
+        let options = VariableStrokeOptions::new(StrokeWidth::Linear {
+            start: 1.0,
+            end: 2.0,
+        });
+        let outline = variable_stroke_outline(&path, &options);
+
+        let mut points = 0;
+        for event in outline.iter() {
+            if let PathEvent::Line { from, to } = event {
+                assert!(from.x.is_finite() && from.y.is_finite());
+                assert!(to.x.is_finite() && to.y.is_finite());
+                points += 1;
+            }
+        }
+        assert!(points > 0);
+    }
+}
@@ -0,0 +1,559 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    reflect::TypeUuid,
+    render::color::Color,
+};
+use svgtypes::{PathParser, PathSegment};
+use tess::{
+    geom::ArcFlags,
+    math::{point, vector, Angle, Point, Transform},
+    path::{builder::SvgPathBuilder, path::Builder, traits::PathBuilder, Path},
+    FillOptions, FillRule, LineCap, LineJoin, StrokeOptions,
+};
+use usvg::NodeExt;
+
+use crate::{DrawMode, Geometry};
+
+/// A shape described by the `d` attribute of an SVG `path` element.
+///
+/// Every SVG path command is supported, both in its absolute and relative
+/// form. Since the y-axis of SVG points down, the path is mirrored vertically,
+/// so that it looks the same as in the editor it was made with.
+///
+/// Requires the `svg` feature.
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{Canvas, DrawMode, SvgPath};
+///
+/// fn heart_system(mut canvas: ResMut<Canvas>) {
+///     let heart: SvgPath = "M 0 30 C -60 -20 -20 -60 0 -30 C 20 -60 60 -20 0 30 Z"
+///         .parse()
+///         .unwrap();
+///
+///     canvas.draw(&heart, DrawMode::fill_simple(), Color::PINK);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SvgPath {
+    path: Path,
+}
+
+impl SvgPath {
+    /// Parses the path data of an SVG `path` element.
+    pub fn parse(data: &str) -> Result<Self, SvgPathError> {
+        let mut b = Builder::new().with_svg();
+        for (i, segment) in PathParser::from(data).enumerate() {
+            let segment = segment.map_err(SvgPathError::Syntax)?;
+            if !is_finite(&segment, b.current_position()) {
+                return Err(SvgPathError::NonFinite { segment: i });
+            }
+            add_segment(&mut b, segment);
+        }
+
+        Ok(Self {
+            path: b.build().transformed(&Transform::scale(1.0, -1.0)),
+        })
+    }
+}
+
+impl FromStr for SvgPath {
+    type Err = SvgPathError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        Self::parse(data)
+    }
+}
+
+impl Geometry for SvgPath {
+    fn generate_path(&self) -> Path {
+        self.path.clone()
+    }
+}
+
+/// The error returned when SVG path data can't be turned into a shape.
+#[derive(Debug, Clone)]
+pub enum SvgPathError {
+    /// The data is malformed. The inner error reports the position of the
+    /// error in the data.
+    Syntax(svgtypes::Error),
+    /// A coordinate of the segment with the given index, counting from zero,
+    /// is too large to be represented as an `f32`.
+    NonFinite { segment: usize },
+}
+
+impl fmt::Display for SvgPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgPathError::Syntax(e) => write!(f, "invalid SVG path data: {}", e),
+            SvgPathError::NonFinite { segment } => {
+                write!(f, "SVG path segment {} has non-finite coordinates", segment)
+            }
+        }
+    }
+}
+
+impl Error for SvgPathError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SvgPathError::Syntax(e) => Some(e),
+            SvgPathError::NonFinite { .. } => None,
+        }
+    }
+}
+
+/// Returns whether the coordinates of `segment` stay finite once converted to
+/// `f32` and, for relative segments, added to the current position.
+fn is_finite(segment: &PathSegment, current: Point) -> bool {
+    let finite = |abs: bool, points: &[(f64, f64)]| {
+        let origin = if abs { point(0.0, 0.0) } else { current };
+        points.iter().all(|&(x, y)| {
+            let p = origin + vector(x as f32, y as f32);
+            p.x.is_finite() && p.y.is_finite()
+        })
+    };
+
+    match *segment {
+        PathSegment::MoveTo { abs, x, y }
+        | PathSegment::LineTo { abs, x, y }
+        | PathSegment::SmoothQuadratic { abs, x, y } => finite(abs, &[(x, y)]),
+        PathSegment::HorizontalLineTo { abs, x } => finite(abs, &[(x, 0.0)]),
+        PathSegment::VerticalLineTo { abs, y } => finite(abs, &[(0.0, y)]),
+        PathSegment::CurveTo {
+            abs,
+            x1,
+            y1,
+            x2,
+            y2,
+            x,
+            y,
+        } => finite(abs, &[(x1, y1), (x2, y2), (x, y)]),
+        PathSegment::SmoothCurveTo { abs, x2, y2, x, y } => finite(abs, &[(x2, y2), (x, y)]),
+        PathSegment::Quadratic { abs, x1, y1, x, y } => finite(abs, &[(x1, y1), (x, y)]),
+        PathSegment::EllipticalArc {
+            abs,
+            rx,
+            ry,
+            x_axis_rotation,
+            x,
+            y,
+            ..
+        } => finite(true, &[(rx, ry), (x_axis_rotation, 0.0)]) && finite(abs, &[(x, y)]),
+        PathSegment::ClosePath { .. } => true,
+    }
+}
+
+/// Adds a segment of SVG path data to the builder, in SVG coordinates.
+fn add_segment(b: &mut impl SvgPathBuilder, segment: PathSegment) {
+    match segment {
+        PathSegment::MoveTo { abs: true, x, y } => {
+            b.move_to(point(x as f32, y as f32));
+        }
+        PathSegment::MoveTo { abs: false, x, y } => {
+            b.relative_move_to(vector(x as f32, y as f32));
+        }
+        PathSegment::LineTo { abs: true, x, y } => {
+            b.line_to(point(x as f32, y as f32));
+        }
+        PathSegment::LineTo { abs: false, x, y } => {
+            b.relative_line_to(vector(x as f32, y as f32));
+        }
+        PathSegment::HorizontalLineTo { abs: true, x } => {
+            b.horizontal_line_to(x as f32);
+        }
+        PathSegment::HorizontalLineTo { abs: false, x } => {
+            b.relative_horizontal_line_to(x as f32);
+        }
+        PathSegment::VerticalLineTo { abs: true, y } => {
+            b.vertical_line_to(y as f32);
+        }
+        PathSegment::VerticalLineTo { abs: false, y } => {
+            b.relative_vertical_line_to(y as f32);
+        }
+        PathSegment::CurveTo {
+            abs,
+            x1,
+            y1,
+            x2,
+            y2,
+            x,
+            y,
+        } => {
+            if abs {
+                b.cubic_bezier_to(
+                    point(x1 as f32, y1 as f32),
+                    point(x2 as f32, y2 as f32),
+                    point(x as f32, y as f32),
+                );
+            } else {
+                b.relative_cubic_bezier_to(
+                    vector(x1 as f32, y1 as f32),
+                    vector(x2 as f32, y2 as f32),
+                    vector(x as f32, y as f32),
+                );
+            }
+        }
+        PathSegment::SmoothCurveTo { abs, x2, y2, x, y } => {
+            if abs {
+                b.smooth_cubic_bezier_to(point(x2 as f32, y2 as f32), point(x as f32, y as f32));
+            } else {
+                b.smooth_relative_cubic_bezier_to(
+                    vector(x2 as f32, y2 as f32),
+                    vector(x as f32, y as f32),
+                );
+            }
+        }
+        PathSegment::Quadratic { abs, x1, y1, x, y } => {
+            if abs {
+                b.quadratic_bezier_to(point(x1 as f32, y1 as f32), point(x as f32, y as f32));
+            } else {
+                b.relative_quadratic_bezier_to(
+                    vector(x1 as f32, y1 as f32),
+                    vector(x as f32, y as f32),
+                );
+            }
+        }
+        PathSegment::SmoothQuadratic { abs, x, y } => {
+            if abs {
+                b.smooth_quadratic_bezier_to(point(x as f32, y as f32));
+            } else {
+                b.smooth_relative_quadratic_bezier_to(vector(x as f32, y as f32));
+            }
+        }
+        PathSegment::EllipticalArc {
+            abs,
+            rx,
+            ry,
+            x_axis_rotation,
+            large_arc,
+            sweep,
+            x,
+            y,
+        } => {
+            let radii = vector(rx as f32, ry as f32);
+            let x_rotation = Angle::degrees(x_axis_rotation as f32);
+            let flags = ArcFlags { large_arc, sweep };
+            if abs {
+                b.arc_to(radii, x_rotation, flags, point(x as f32, y as f32));
+            } else {
+                b.relative_arc_to(radii, x_rotation, flags, vector(x as f32, y as f32));
+            }
+        }
+        PathSegment::ClosePath { .. } => b.close(),
+    }
+}
+
+/// A shape of an [`SvgDocument`], with the style it is drawn with.
+#[derive(Clone)]
+pub struct SvgShape {
+    pub path: Path,
+    pub draw_mode: DrawMode,
+    pub color: Color,
+}
+
+/// An SVG document, loaded as an asset from a `.svg` file.
+///
+/// The document is flattened into a list of shapes: groups and their
+/// transforms are applied to the paths, basic shapes like `rect`, `circle` or
+/// `polyline` are converted to paths, and each fill and stroke of a path
+/// becomes a separate shape. Like [`SvgPath`], the document is mirrored
+/// vertically.
+///
+/// Only solid colors are supported: fills and strokes painted with gradients
+/// or patterns are skipped. Clip paths, masks, group opacity and
+/// `stroke-dasharray` are ignored, and the content of `defs` is never drawn.
+///
+/// Requires the `svg` feature.
+///
+/// ## Usage
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_canvas::{Canvas, SvgDocument};
+///
+/// struct Logo(Handle<SvgDocument>);
+///
+/// fn setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands.insert_resource(Logo(asset_server.load("logo.svg")));
+/// }
+///
+/// fn logo_system(
+///     mut canvas: ResMut<Canvas>,
+///     logo: Res<Logo>,
+///     documents: Res<Assets<SvgDocument>>,
+/// ) {
+///     if let Some(document) = documents.get(&logo.0) {
+///         canvas.draw_svg(document);
+///     }
+/// }
+/// ```
+#[derive(Clone, TypeUuid)]
+#[uuid = "6d0c5c76-0d1e-4c8e-9d1b-5a0f3b6a8c2e"]
+pub struct SvgDocument {
+    /// The shapes of the document, in drawing order.
+    pub shapes: Vec<SvgShape>,
+}
+
+impl SvgDocument {
+    /// Parses the content of an SVG file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, usvg::Error> {
+        let options = usvg::Options::default();
+        let tree = usvg::Tree::from_data(bytes, &options.to_ref())?;
+
+        let mut shapes = Vec::new();
+        for node in tree.root().descendants() {
+            // Definitions like clip paths, masks and patterns are only drawn
+            // through the elements that use them.
+            if tree.is_in_defs(&node) {
+                continue;
+            }
+
+            if let usvg::NodeKind::Path(ref svg_path) = *node.borrow() {
+                let mut t = node.abs_transform();
+                t.append(&svg_path.transform);
+                let transform = Transform::new(
+                    t.a as f32, t.b as f32, t.c as f32, t.d as f32, t.e as f32, t.f as f32,
+                )
+                .then_scale(1.0, -1.0);
+                let path = build_path(&svg_path.data).transformed(&transform);
+
+                if let Some(fill) = &svg_path.fill {
+                    if let Some(color) = solid_color(&fill.paint, fill.opacity) {
+                        let fill_rule = match fill.rule {
+                            usvg::FillRule::NonZero => FillRule::NonZero,
+                            usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+                        };
+                        shapes.push(SvgShape {
+                            path: path.clone(),
+                            draw_mode: DrawMode::Fill(
+                                FillOptions::default().with_fill_rule(fill_rule),
+                            ),
+                            color,
+                        });
+                    }
+                }
+
+                if let Some(stroke) = &svg_path.stroke {
+                    if let Some(color) = solid_color(&stroke.paint, stroke.opacity) {
+                        shapes.push(SvgShape {
+                            path,
+                            draw_mode: DrawMode::Stroke(stroke_options(stroke, &t)),
+                            color,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(Self { shapes })
+    }
+}
+
+/// Loads `.svg` files as [`SvgDocument`]s.
+#[derive(Default)]
+pub struct SvgLoader;
+
+impl AssetLoader for SvgLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let document = SvgDocument::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(document));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["svg"]
+    }
+}
+
+/// Converts path data in SVG coordinates to a Lyon `Path`.
+fn build_path(data: &usvg::PathData) -> Path {
+    let mut b = Builder::new();
+    let mut in_subpath = false;
+    for segment in data.iter() {
+        match *segment {
+            usvg::PathSegment::MoveTo { x, y } => {
+                if in_subpath {
+                    b.end(false);
+                }
+                b.begin(point(x as f32, y as f32));
+                in_subpath = true;
+            }
+            usvg::PathSegment::LineTo { x, y } => {
+                b.line_to(point(x as f32, y as f32));
+            }
+            usvg::PathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                b.cubic_bezier_to(
+                    point(x1 as f32, y1 as f32),
+                    point(x2 as f32, y2 as f32),
+                    point(x as f32, y as f32),
+                );
+            }
+            usvg::PathSegment::ClosePath => {
+                b.end(true);
+                in_subpath = false;
+            }
+        }
+    }
+    if in_subpath {
+        b.end(false);
+    }
+
+    b.build()
+}
+
+/// Returns the color of a paint, unless it is a gradient or a pattern.
+fn solid_color(paint: &usvg::Paint, opacity: usvg::Opacity) -> Option<Color> {
+    match paint {
+        usvg::Paint::Color(c) => Some(Color::rgba_u8(
+            c.red,
+            c.green,
+            c.blue,
+            (opacity.value() * 255.0).round() as u8,
+        )),
+        _ => None,
+    }
+}
+
+/// Converts an SVG stroke to the stroke options of a path with the given
+/// transform.
+///
+/// Lyon strokes the transformed path, so the width is scaled by the average
+/// scale of the transform.
+fn stroke_options(stroke: &usvg::Stroke, transform: &usvg::Transform) -> StrokeOptions {
+    let (sx, sy) = transform.get_scale();
+    let line_width = stroke.width.value() * (sx * sy).abs().sqrt();
+    let line_cap = match stroke.linecap {
+        usvg::LineCap::Butt => LineCap::Butt,
+        usvg::LineCap::Round => LineCap::Round,
+        usvg::LineCap::Square => LineCap::Square,
+    };
+    let line_join = match stroke.linejoin {
+        usvg::LineJoin::Miter => LineJoin::Miter,
+        usvg::LineJoin::Round => LineJoin::Round,
+        usvg::LineJoin::Bevel => LineJoin::Bevel,
+    };
+
+    StrokeOptions::default()
+        .with_line_width(line_width as f32)
+        .with_line_cap(line_cap)
+        .with_line_join(line_join)
+        .with_miter_limit(stroke.miterlimit.value() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use tess::path::PathEvent;
+
+    use super::*;
+
+    fn events(data: &str) -> Vec<PathEvent> {
+        SvgPath::parse(data).unwrap().path.iter().collect()
+    }
+
+    #[test]
+    fn relative_commands_follow_the_current_point() {
+        let events = events("m 10 10 l 10 0 v 10 h -10 z");
+        assert_eq!(
+            events[0],
+            PathEvent::Begin {
+                at: point(10.0, -10.0)
+            }
+        );
+        let ends = events
+            .iter()
+            .filter_map(|event| match *event {
+                PathEvent::Line { to, .. } => Some(to),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ends,
+            vec![point(20.0, -10.0), point(20.0, -20.0), point(10.0, -20.0)]
+        );
+    }
+
+    #[test]
+    fn smooth_curves_reflect_the_previous_control_point() {
+        let cubic = events("M 0 0 C 0 10 10 10 10 0 S 20 -10 20 0");
+        match cubic[2] {
+            PathEvent::Cubic { ctrl1, .. } => assert_eq!(ctrl1, point(10.0, 10.0)),
+            ref event => panic!("unexpected event {:?}", event),
+        }
+
+        let quadratic = events("M 0 0 Q 5 10 10 0 T 20 0");
+        match quadratic[2] {
+            PathEvent::Quadratic { ctrl, to, .. } => {
+                assert_eq!(ctrl, point(15.0, 10.0));
+                assert_eq!(to, point(20.0, 0.0));
+            }
+            ref event => panic!("unexpected event {:?}", event),
+        }
+    }
+
+    #[test]
+    fn arcs_reach_their_end_point() {
+        let events = events("M 0 0 A 10 10 0 0 1 20 0");
+        let mut last = point(0.0, 0.0);
+        let mut top = 0.0f32;
+        for event in &events {
+            if let PathEvent::Quadratic { to, .. } | PathEvent::Cubic { to, .. } = *event {
+                last = to;
+                top = top.max(to.y);
+            }
+        }
+        assert!((last - point(20.0, 0.0)).length() < 1e-3);
+        // The sweep flag makes the arc go up in SVG, which is mirrored.
+        assert!(top > 7.0 && top < 10.001);
+    }
+
+    #[test]
+    fn malformed_data_is_rejected() {
+        let error = SvgPath::parse("M 10 L").unwrap_err();
+        assert!(matches!(error, SvgPathError::Syntax(_)));
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn non_finite_coordinates_are_rejected() {
+        let error = SvgPath::parse("M 0 0 L 1e39 0").unwrap_err();
+        assert!(matches!(error, SvgPathError::NonFinite { segment: 1 }));
+
+        let error = SvgPath::parse("M 3e38 0 l 3e38 0").unwrap_err();
+        assert!(matches!(error, SvgPathError::NonFinite { segment: 1 }));
+    }
+
+    #[test]
+    fn document_skips_clip_paths() {
+        let data = br#"
+            <svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+                <defs>
+                    <clipPath id="clip">
+                        <circle cx="50" cy="50" r="40"/>
+                    </clipPath>
+                </defs>
+                <rect width="100" height="100" fill="red" clip-path="url(#clip)"/>
+                <rect x="10" y="10" width="20" height="20" fill="blue" stroke="black"/>
+            </svg>
+        "#;
+        let document = SvgDocument::from_bytes(data).unwrap();
+
+        // The fill of the first rectangle, and the fill and the stroke of the
+        // second one.
+        assert_eq!(document.shapes.len(), 3);
+    }
+}
@@ -0,0 +1,207 @@
+use ab_glyph::{Font as _, FontArc, GlyphId, InvalidFont, OutlineCurve, ScaleFont};
+use bevy::math::Vec2;
+use tess::{
+    math::{point, Point},
+    path::{path::Builder, traits::PathBuilder, Path},
+};
+
+use crate::Geometry;
+
+/// A TrueType or OpenType font, used to draw [`CanvasText`].
+///
+/// Cloning a font is cheap, since the font data is shared.
+#[derive(Debug, Clone)]
+pub struct CanvasFont(FontArc);
+
+impl CanvasFont {
+    /// Parses a font from the content of a TTF or OTF file.
+    pub fn try_from_bytes(bytes: Vec<u8>) -> Result<Self, InvalidFont> {
+        FontArc::try_from_vec(bytes).map(Self)
+    }
+}
+
+/// How the lines of a [`CanvasText`] are aligned to its position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HorizontalAlign {
+    /// The left edge of each line is at the position of the text.
+    Left,
+    Center,
+    Right,
+}
+
+impl Default for HorizontalAlign {
+    fn default() -> Self {
+        Self::Left
+    }
+}
+
+/// How the block of lines of a [`CanvasText`] is aligned to its position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerticalAlign {
+    /// The ascender of the first line is at the position of the text.
+    Top,
+    Center,
+    /// The baseline of the first line is at the position of the text.
+    Baseline,
+    /// The descender of the last line is at the position of the text.
+    Bottom,
+}
+
+impl Default for VerticalAlign {
+    fn default() -> Self {
+        Self::Baseline
+    }
+}
+
+/// A string of text, outlined from the glyphs of a [`CanvasFont`].
+///
+/// Text is drawn like any other shape: its outline goes through the fill
+/// tessellator, so it can be transformed, painted with gradients and sorted
+/// with the other shapes of the canvas. The [`Canvas::draw_text`] method fills
+/// the glyphs with the nonzero rule required by most fonts.
+///
+/// Lines are separated by `'\n'` or `"\r\n"`. Characters that aren't in the
+/// font are drawn with its missing glyph, if any.
+///
+/// [`Canvas::draw_text`]: crate::Canvas::draw_text
+///
+/// ## Usage
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_canvas::{Canvas, CanvasFont, CanvasText, HorizontalAlign, VerticalAlign};
+///
+/// struct LabelFont(CanvasFont);
+///
+/// fn setup_system(mut commands: Commands) {
+///     let bytes = std::fs::read("assets/fonts/FiraSans-Bold.ttf").unwrap();
+///     commands.insert_resource(LabelFont(CanvasFont::try_from_bytes(bytes).unwrap()));
+/// }
+///
+/// fn label_system(mut canvas: ResMut<Canvas>, font: Res<LabelFont>) {
+///     let text = CanvasText {
+///         value: "Hello, canvas!".to_string(),
+///         font: font.0.clone(),
+///         size: 40.0,
+///         position: Vec2::ZERO,
+///         horizontal_align: HorizontalAlign::Center,
+///         vertical_align: VerticalAlign::Center,
+///     };
+///
+///     canvas.draw_text(&text, Color::WHITE);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CanvasText {
+    pub value: String,
+    pub font: CanvasFont,
+    /// The distance between the ascender and the descender of the font, in
+    /// canvas units.
+    pub size: f32,
+    /// Reference point of the text, see the alignment fields.
+    pub position: Vec2,
+    pub horizontal_align: HorizontalAlign,
+    pub vertical_align: VerticalAlign,
+}
+
+impl Geometry for CanvasText {
+    fn generate_path(&self) -> Path {
+        let font = self.font.0.as_scaled(self.size);
+        let scale = Vec2::new(font.h_scale_factor(), font.v_scale_factor());
+        let line_advance = font.height() + font.line_gap();
+
+        let lines: Vec<&str> = self.value.lines().collect();
+        let block_height = font.height() + line_advance * lines.len().saturating_sub(1) as f32;
+        let first_baseline = self.position.y
+            + match self.vertical_align {
+                VerticalAlign::Top => -font.ascent(),
+                VerticalAlign::Center => block_height / 2.0 - font.ascent(),
+                VerticalAlign::Baseline => 0.0,
+                VerticalAlign::Bottom => block_height - font.ascent(),
+            };
+
+        let mut b = Builder::new();
+        for (i, line) in lines.iter().enumerate() {
+            let glyphs = layout_line(&font, line);
+            let width = glyphs.last().map_or(0.0, |&(id, x)| x + font.h_advance(id));
+            let x = self.position.x
+                - match self.horizontal_align {
+                    HorizontalAlign::Left => 0.0,
+                    HorizontalAlign::Center => width / 2.0,
+                    HorizontalAlign::Right => width,
+                };
+            let baseline = first_baseline - line_advance * i as f32;
+
+            for (id, glyph_x) in glyphs {
+                let origin = Vec2::new(x + glyph_x, baseline);
+                if let Some(outline) = font.font().outline(id) {
+                    add_outline(&mut b, &outline.curves, origin, scale);
+                }
+            }
+        }
+
+        b.build()
+    }
+}
+
+/// Returns the glyphs of a line of text, with their horizontal offset from the
+/// start of the line.
+fn layout_line<F: ab_glyph::Font>(font: &impl ScaleFont<F>, line: &str) -> Vec<(GlyphId, f32)> {
+    let mut caret = 0.0;
+    let mut previous = None;
+    let mut glyphs = Vec::with_capacity(line.len());
+    for c in line.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += font.kern(previous, id);
+        }
+        glyphs.push((id, caret));
+        caret += font.h_advance(id);
+        previous = Some(id);
+    }
+
+    glyphs
+}
+
+/// Adds the contours of a glyph, defined in font units, to the path.
+///
+/// The curves of a glyph are stored one after the other: a curve that doesn't
+/// start where the previous one ended starts a new contour.
+fn add_outline(b: &mut Builder, curves: &[OutlineCurve], origin: Vec2, scale: Vec2) {
+    let to_canvas =
+        |p: ab_glyph::Point| -> Point { point(origin.x + p.x * scale.x, origin.y + p.y * scale.y) };
+
+    let mut contour_end: Option<ab_glyph::Point> = None;
+    for curve in curves {
+        let start = match curve {
+            OutlineCurve::Line(p0, _)
+            | OutlineCurve::Quad(p0, _, _)
+            | OutlineCurve::Cubic(p0, _, _, _) => *p0,
+        };
+        if contour_end != Some(start) {
+            if contour_end.is_some() {
+                b.close();
+            }
+            b.begin(to_canvas(start));
+        }
+
+        let end = match *curve {
+            OutlineCurve::Line(_, p1) => {
+                b.line_to(to_canvas(p1));
+                p1
+            }
+            OutlineCurve::Quad(_, p1, p2) => {
+                b.quadratic_bezier_to(to_canvas(p1), to_canvas(p2));
+                p2
+            }
+            OutlineCurve::Cubic(_, p1, p2, p3) => {
+                b.cubic_bezier_to(to_canvas(p1), to_canvas(p2), to_canvas(p3));
+                p3
+            }
+        };
+        contour_end = Some(end);
+    }
+
+    if contour_end.is_some() {
+        b.close();
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::{math::Vec2, transform::components::Transform};
+use tess::math::{Angle, Transform as LyonTransform};
+
+use crate::render::types::Conversion;
+
+/// An affine transformation of the 2D plane.
+///
+/// Transforms can be chained with [`then`](Transform2d::then) to combine
+/// translations, rotations, scalings and skews.
+///
+/// ## Usage
+/// ```
+/// use bevy::math::Vec2;
+/// use bevy_canvas::Transform2d;
+///
+/// // Rotate by 90 degrees around the origin, then move up.
+/// let transform = Transform2d::from_rotation(std::f32::consts::FRAC_PI_2)
+///     .then(Transform2d::from_translation(Vec2::new(0.0, 100.0)));
+///
+/// let p = transform.transform_point(Vec2::new(10.0, 0.0));
+/// assert!((p - Vec2::new(0.0, 110.0)).length() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2d(pub(crate) LyonTransform);
+
+impl Transform2d {
+    /// The transform that leaves every point in place.
+    pub fn identity() -> Self {
+        Self(LyonTransform::identity())
+    }
+
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self(LyonTransform::translation(translation.x, translation.y))
+    }
+
+    /// Creates a counterclockwise rotation around the origin. The angle is
+    /// expressed in radians.
+    pub fn from_rotation(angle: f32) -> Self {
+        Self(LyonTransform::rotation(Angle::radians(angle)))
+    }
+
+    pub fn from_scale(scale: Vec2) -> Self {
+        Self(LyonTransform::scale(scale.x, scale.y))
+    }
+
+    /// Creates a skew transformation. The components of `skew` are the angles,
+    /// in radians, by which the x-axis and the y-axis get slanted.
+    pub fn from_skew(skew: Vec2) -> Self {
+        Self(LyonTransform::new(
+            1.0,
+            skew.y.tan(),
+            skew.x.tan(),
+            1.0,
+            0.0,
+            0.0,
+        ))
+    }
+
+    /// Returns a transform that applies `self`, then `other`.
+    pub fn then(self, other: Self) -> Self {
+        Self(self.0.then(&other.0))
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        let p = self.0.transform_point(point.to_point());
+        Vec2::new(p.x, p.y)
+    }
+
+    /// Returns the transform that reverts `self`, if any.
+    pub fn inverse(&self) -> Option<Self> {
+        self.0.inverse().map(Self)
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.0 == LyonTransform::identity()
+    }
+}
+
+impl Default for Transform2d {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Projects a Bevy `Transform` on the xy-plane, dropping its z-component.
+impl From<Transform> for Transform2d {
+    fn from(transform: Transform) -> Self {
+        let m = transform.compute_matrix();
+        Self(LyonTransform::new(
+            m.x_axis.x, m.x_axis.y, m.y_axis.x, m.y_axis.y, m.w_axis.x, m.w_axis.y,
+        ))
+    }
+}